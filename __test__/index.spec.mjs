@@ -1,6 +1,24 @@
+import { spawn } from 'node:child_process'
+import { once } from 'node:events'
+import fs from 'node:fs'
+import os from 'node:os'
+import path from 'node:path'
+import { fileURLToPath } from 'node:url'
+
 import test from 'ava'
 
-import { cpuFeatures, SysInfo } from '../index.js'
+import { cpuFeatures, cpuVulnerabilities, SysInfo, virtualization, x86MicroarchLevel } from '../index.js'
+
+const fixture = (name) => fileURLToPath(new URL(`./fixtures/${name}`, import.meta.url))
+
+/** Thread ids of `pid` on Linux, empty if the process exited */
+const readTasks = (pid) => {
+  try {
+    return fs.readdirSync(`/proc/${pid}/task`).map(Number)
+  } catch {
+    return []
+  }
+}
 
 test('cpuFeatures', (t) => {
   const { arch } = cpuFeatures()
@@ -16,3 +34,445 @@ test('SysInfo', (t) => {
     t.is(typeof cpu.frequency(), 'number')
   }
 })
+
+test('processes', (t) => {
+  const sysinfo = new SysInfo()
+  const processes = sysinfo.processes()
+  t.true(processes.length > 0)
+  const current = processes.find((p) => p.pid() === process.pid)
+  t.truthy(current)
+  t.is(current.ppid(), process.ppid)
+  t.is(typeof current.name(), 'string')
+  t.true(Array.isArray(current.cmd()))
+  t.is(typeof current.memory(), 'bigint')
+})
+
+test('process lookup', async (t) => {
+  // ava runs the tests in a worker, the cmdline of `process.pid` names ava rather than this file
+  const marker = `sysinfo-lookup-${process.pid}-${Date.now()}`
+  const child = spawn(process.execPath, ['-e', 'setInterval(() => {}, 1000)', marker])
+  const exited = once(child, 'exit')
+  try {
+    const sysinfo = new SysInfo()
+    t.is(sysinfo.process(process.pid).pid(), process.pid)
+    t.is(sysinfo.process(0xfffffff0), null)
+    const name = sysinfo.process(process.pid).name()
+    t.true(sysinfo.processesByName(name).some((p) => p.pid() === process.pid))
+    t.true(sysinfo.processesByName(name, { exact: true }).every((p) => p.name() === name))
+    t.deepEqual(
+      sysinfo.processesByName(marker, { matchCmdline: true }).map((p) => p.pid()),
+      [child.pid],
+    )
+    t.deepEqual(sysinfo.processesByName('no-such-process-name-\u{1F600}'), [])
+  } finally {
+    child.kill()
+    await exited
+  }
+})
+
+test('process lists leave threads out', (t) => {
+  const sysinfo = new SysInfo()
+  const name = sysinfo.process(process.pid).name()
+  for (const processes of [sysinfo.processes(), sysinfo.processesByName(name)]) {
+    const pids = new Set(processes.map((p) => p.pid()))
+    for (const pid of pids) {
+      const tasks = process.platform === 'linux' ? readTasks(pid) : []
+      t.false(
+        tasks.some((tid) => tid !== pid && pids.has(tid)),
+        `threads of ${pid} are listed as processes`,
+      )
+    }
+  }
+})
+
+test('kill process', async (t) => {
+  const child = spawn(process.execPath, ['-e', 'setInterval(() => {}, 1000)'])
+  const exited = once(child, 'exit')
+  try {
+    const sysinfo = new SysInfo()
+    t.deepEqual(sysinfo.killProcess(0xfffffff0), { sent: false, reason: 'NotFound' })
+    // Windows only supports `Kill`
+    const windows = process.platform === 'win32'
+    const result = sysinfo.process(child.pid).kill(windows ? 'Kill' : 'Term')
+    t.true(result.sent)
+    const [, signal] = await exited
+    if (!windows) {
+      t.is(signal, 'SIGTERM')
+    }
+  } finally {
+    child.kill('SIGKILL')
+    await exited
+  }
+})
+
+test('process tree', async (t) => {
+  const child = spawn(process.execPath, ['-e', 'setInterval(() => {}, 1000)'])
+  const exited = once(child, 'exit')
+  try {
+    const sysinfo = new SysInfo()
+    const [root] = sysinfo.processTree(process.pid)
+    t.is(root.pid, process.pid)
+    t.true(root.children.some((node) => node.pid === child.pid))
+    t.deepEqual(sysinfo.processTree(0xfffffff0), [])
+    t.true(sysinfo.processTree().length > 0)
+
+    const current = sysinfo.process(process.pid)
+    t.true(current.descendants().some((p) => p.pid() === child.pid))
+    t.is(sysinfo.process(child.pid).ancestors()[0].pid(), process.pid)
+  } finally {
+    child.kill()
+    await exited
+  }
+})
+
+test('process disk usage', (t) => {
+  const sysinfo = new SysInfo()
+  const usage = sysinfo.process(process.pid).diskUsage()
+  t.is(typeof usage.totalReadBytes, 'bigint')
+  t.true(usage.totalWrittenBytes >= usage.writtenBytes)
+  const top = sysinfo.topProcessesByIo(3)
+  t.true(top.length <= 3)
+  const io = (p) => p.diskUsage().readBytes + p.diskUsage().writtenBytes
+  for (let i = 1; i < top.length; i++) {
+    t.true(io(top[i - 1]) >= io(top[i]))
+  }
+})
+
+test('selective refresh', (t) => {
+  const sysinfo = new SysInfo({ memory: { ram: true } })
+  t.true(sysinfo.totalMemory() > 0n)
+  t.is(sysinfo.totalSwap(), 0n)
+  t.is(sysinfo.processes().length, 0)
+  t.notThrows(() => sysinfo.refresh({ memory: { swap: true }, processes: { memory: true } }))
+  t.true(sysinfo.process(process.pid).memory() > 0n)
+  t.notThrows(() => sysinfo.refreshCpu())
+  t.true(sysinfo.cpus().length > 0)
+})
+
+test('selective refresh updates process fields that changed', async (t) => {
+  // ava runs the tests in workers, which can't `process.chdir()`, so a child changes directory
+  const tempDir = () => fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'sysinfo-')))
+  const [from, to] = [tempDir(), tempDir()]
+  const child = spawn(
+    process.execPath,
+    ['-e', "process.stdin.once('data', (dir) => { process.chdir(String(dir).trim()); console.log('moved') })"],
+    { cwd: from },
+  )
+  const exited = once(child, 'exit')
+  try {
+    const kinds = { processes: { cwd: true } }
+    const sysinfo = new SysInfo(kinds)
+    t.is(sysinfo.process(child.pid).cwd(), from)
+    const moved = once(child.stdout, 'data')
+    child.stdin.write(`${to}\n`)
+    await moved
+    sysinfo.refresh(kinds)
+    t.is(sysinfo.process(child.pid).cwd(), to)
+  } finally {
+    child.kill()
+    await exited
+    for (const dir of [from, to]) {
+      fs.rmSync(dir, { recursive: true })
+    }
+  }
+})
+
+test('process handles read live data', async (t) => {
+  const child = spawn(process.execPath, ['-e', 'setInterval(() => {}, 1000)'])
+  const exited = once(child, 'exit')
+  const sysinfo = new SysInfo()
+  const handle = sysinfo.process(child.pid)
+  t.is(typeof handle.name(), 'string')
+  child.kill()
+  await exited
+  sysinfo.refreshProcesses()
+  t.is(handle.pid(), child.pid)
+  t.throws(() => handle.name())
+  t.deepEqual(handle.kill(), { sent: false, reason: 'NotFound' })
+})
+
+test('sample cpu usage', async (t) => {
+  const sysinfo = new SysInfo()
+  const usage = await sysinfo.sampleCpuUsage(250)
+  t.is(usage.cpus.length, sysinfo.cpus().length)
+  t.true(usage.global >= 0 && usage.global <= 100)
+  t.is(typeof sysinfo.globalCpuUsage(), 'number')
+  t.is(sysinfo.globalCpuUsage(), usage.global)
+  t.deepEqual(
+    sysinfo.cpus().map((cpu) => cpu.usage()),
+    usage.cpus,
+  )
+})
+
+test('getters do not wait for an async refresh', async (t) => {
+  const sysinfo = new SysInfo({ cpu: { usage: true } })
+  const sample = sysinfo.sampleCpuUsage(1000)
+  await new Promise((resolve) => setTimeout(resolve, 100))
+  const start = Date.now()
+  t.is(typeof sysinfo.globalCpuUsage(), 'number')
+  t.true(sysinfo.cpus().every((cpu) => typeof cpu.usage() === 'number'))
+  t.true(Date.now() - start < 500)
+  const usage = await sample
+  t.is(sysinfo.globalCpuUsage(), usage.global)
+})
+
+test('async create and refresh', async (t) => {
+  const sysinfo = await SysInfo.create({ memory: { ram: true }, cpu: { frequency: true } })
+  t.true(sysinfo instanceof SysInfo)
+  t.true(sysinfo.totalMemory() > 0n)
+  const cpus = sysinfo.cpus()
+  await sysinfo.refreshAsync({ cpu: { usage: true }, processes: {} })
+  t.true(sysinfo.process(process.pid) !== null)
+  for (const cpu of cpus) {
+    t.is(typeof cpu.usage(), 'number')
+  }
+  await t.notThrowsAsync(sysinfo.refreshAsync())
+})
+
+test('cpu handles read live data across refreshes', async (t) => {
+  const sysinfo = new SysInfo({ cpu: { usage: true } })
+  const [cpu] = sysinfo.cpus()
+  const before = cpu.usage()
+  let after = before
+  // Keep the cpus busy until a refresh reports a different usage
+  for (let attempt = 0; attempt < 20 && after === before; attempt++) {
+    const end = Date.now() + 250
+    while (Date.now() < end) {}
+    sysinfo.refreshCpu()
+    after = sysinfo.cpus()[0].usage()
+  }
+  t.not(after, before)
+  t.is(cpu.usage(), after)
+  await sysinfo.refreshAsync({ cpu: { usage: true } })
+  t.is(cpu.usage(), sysinfo.cpus()[0].usage())
+})
+
+test('watch as async iterator', async (t) => {
+  const sysinfo = new SysInfo({ memory: { ram: true } })
+  const watcher = sysinfo.watch({ intervalMs: 200, kinds: { cpu: { usage: true }, memory: { ram: true } } })
+  const snapshots = []
+  for await (const snapshot of watcher) {
+    snapshots.push(snapshot)
+    if (snapshots.length === 2) {
+      break
+    }
+  }
+  t.is(snapshots.length, 2)
+  t.true(snapshots[1].timestamp > snapshots[0].timestamp)
+  t.true(snapshots[0].totalMemory > 0n)
+  t.is(snapshots[0].cpus.length, new SysInfo({ cpu: { usage: true } }).cpus().length)
+  // The watcher samples its own System
+  t.is(sysinfo.cpus().length, 0)
+})
+
+test('watch with callback', async (t) => {
+  const sysinfo = new SysInfo()
+  const snapshot = await new Promise((resolve) => {
+    const watcher = sysinfo.watch({ intervalMs: 200 }, (snapshot) => {
+      watcher.stop()
+      resolve(snapshot)
+    })
+  })
+  t.is(typeof snapshot.globalCpuUsage, 'number')
+  t.is(typeof snapshot.loadAverage.one, 'number')
+})
+
+test('disks', (t) => {
+  const sysinfo = new SysInfo({})
+  const disks = sysinfo.disks()
+  t.true(Array.isArray(disks))
+  for (const disk of disks) {
+    t.is(typeof disk.mountPoint, 'string')
+    t.true(['HDD', 'SSD', 'Unknown'].includes(disk.kind))
+    t.true(disk.availableSpace <= disk.totalSpace)
+  }
+  t.notThrows(() => sysinfo.refreshDisks())
+})
+
+test('networks', (t) => {
+  const sysinfo = new SysInfo({})
+  const networks = sysinfo.networks()
+  t.true(Array.isArray(networks))
+  for (const network of networks) {
+    t.is(typeof network.name, 'string')
+    t.is(typeof network.macAddress, 'string')
+    t.true(network.received <= network.totalReceived)
+    for (const ip of network.ipNetworks) {
+      t.is(typeof ip.addr, 'string')
+      t.true(ip.prefix <= 128)
+    }
+  }
+  t.notThrows(() => sysinfo.refreshNetworks())
+})
+
+test('components', (t) => {
+  const sysinfo = new SysInfo({})
+  for (const component of sysinfo.components()) {
+    t.is(typeof component.label, 'string')
+  }
+  t.notThrows(() => sysinfo.refreshComponentsList())
+})
+
+test('components from a sysfs fixture', (t) => {
+  const sysinfo = new SysInfo({})
+  t.deepEqual(sysinfo.components({ readRoot: fixture('sysfs') }), [
+    { label: 'coretemp Package id 0', temperature: 45, max: 80, critical: 100 },
+    { label: 'coretemp Core 0', temperature: 43.5 },
+    { label: 'x86_pkg_temp', temperature: 47, critical: 105 },
+  ])
+})
+
+test('users and groups', (t) => {
+  const sysinfo = new SysInfo({})
+  const users = sysinfo.users()
+  t.true(Array.isArray(users))
+  for (const user of users) {
+    t.is(typeof user.uid, 'string')
+    t.is(typeof user.name, 'string')
+    t.true(Array.isArray(user.groups))
+  }
+  t.true(Array.isArray(sysinfo.groups()))
+  t.notThrows(() => sysinfo.refreshUsers())
+})
+
+test('process owner', (t) => {
+  const sysinfo = new SysInfo({ processes: { user: true } })
+  const current = sysinfo.process(process.pid)
+  const owner = current.user()
+  if (process.platform !== 'win32' && owner !== null) {
+    const user = sysinfo.users().find((user) => user.uid === String(process.getuid()))
+    t.is(owner, user?.name)
+  } else {
+    t.pass()
+  }
+})
+
+test('uptime', (t) => {
+  const sysinfo = new SysInfo({})
+  const uptime = sysinfo.uptime()
+  t.is(typeof uptime, 'number')
+  t.true(uptime > 0)
+  const { seconds, idleSeconds, bootTime } = sysinfo.uptimeDetailed()
+  t.true(Math.abs(seconds - uptime) < 5)
+  t.true(Math.abs(bootTime - (Date.now() / 1000 - seconds)) < 5)
+  if (process.platform === 'linux') {
+    t.true(idleSeconds >= 0)
+  }
+})
+
+test('x86 microarch level', (t) => {
+  const level = x86MicroarchLevel()
+  t.deepEqual(cpuFeatures().x86Level ?? null, level)
+  if (process.arch === 'x64') {
+    t.true(level.level >= 1 && level.level <= 4)
+    t.is(level.name, `x86-64-v${level.level}`)
+    t.is(level.missingForNext.length === 0, level.level === 4)
+  } else {
+    t.is(level, null)
+  }
+})
+
+test('cpu caches', (t) => {
+  const { caches } = cpuFeatures()
+  t.true(Array.isArray(caches))
+  for (const cache of caches) {
+    t.true(['Data', 'Instruction', 'Unified'].includes(cache.type))
+    t.true(cache.level >= 1)
+    t.true(cache.size > 0)
+    t.true(cache.sharedBy >= 1)
+  }
+})
+
+test('cpu topology', (t) => {
+  const sysinfo = new SysInfo()
+  const topology = sysinfo.cpuTopology()
+  t.true(topology.length >= 1)
+  for (const pkg of topology) {
+    t.deepEqual(
+      pkg.cores.map((core) => core.id),
+      pkg.cores.map((_, index) => index),
+    )
+  }
+  const cores = topology.flatMap((pkg) => pkg.cores)
+  const logical = cores.flatMap((core) => core.cpus)
+  t.is(new Set(logical).size, logical.length)
+  t.true(logical.length >= sysinfo.cpus().length)
+  const physical = sysinfo.physicalCoreCount()
+  if (physical !== null) {
+    t.true(physical >= 1 && physical <= logical.length)
+  }
+})
+
+test('cpu core type', (t) => {
+  const sysinfo = new SysInfo()
+  for (const cpu of sysinfo.cpus()) {
+    t.true(['performance', 'efficiency', 'unknown'].includes(cpu.coreType()))
+  }
+})
+
+test('cgroup limits', (t) => {
+  const sysinfo = new SysInfo({})
+  const limits = sysinfo.cgroupLimits()
+  if (limits !== null) {
+    t.true([1, 2].includes(limits.version))
+    if (limits.cpuQuota !== undefined) {
+      t.true(limits.cpuQuota > 0)
+    }
+  }
+  const parallelism = sysinfo.effectiveParallelism()
+  t.true(Number.isInteger(parallelism) && parallelism >= 1)
+})
+
+test('pressure', (t) => {
+  const sysinfo = new SysInfo({})
+  const pressure = sysinfo.pressure()
+  if (pressure !== null) {
+    for (const resource of [pressure.cpu, pressure.memory, pressure.io].filter(Boolean)) {
+      t.true(resource.some.avg10 >= 0 && resource.some.avg10 <= 100)
+      t.is(typeof resource.some.total, 'bigint')
+    }
+  }
+  t.notThrows(() => sysinfo.cgroupPressure())
+})
+
+test('memory details', (t) => {
+  const details = new SysInfo({}).memoryDetails()
+  if (process.platform !== 'linux') {
+    t.is(details, null)
+    return
+  }
+  t.is(details.fields.MemTotal, details.total)
+  t.is(typeof details.fields.Committed_AS, 'bigint')
+  t.false(Object.keys(details.fields).some((name) => name.startsWith('HugePages_')))
+  t.is(details.used, details.total - details.available)
+  t.is(details.swapUsed + details.swapFree, details.swapTotal)
+})
+
+test('virtualization', (t) => {
+  const { hypervisor, container, isWsl } = virtualization()
+  for (const name of [hypervisor, container].filter((name) => name !== undefined)) {
+    t.true(/^[a-z]+$/.test(name))
+  }
+  t.is(typeof isWsl, 'boolean')
+  if (process.platform !== 'linux') {
+    t.false(isWsl)
+  }
+})
+
+test('cpu vulnerabilities', (t) => {
+  const vulnerabilities = cpuVulnerabilities()
+  t.true(Array.isArray(vulnerabilities))
+  for (const { name, status, detail } of vulnerabilities) {
+    t.true(/^[a-z0-9_]+$/.test(name))
+    t.true(['vulnerable', 'mitigated', 'not affected'].includes(status))
+    if (status === 'not affected') {
+      t.is(detail, undefined)
+    }
+  }
+  if (process.platform === 'linux' && fs.existsSync('/sys/devices/system/cpu/vulnerabilities')) {
+    t.deepEqual(
+      vulnerabilities.map(({ name }) => name),
+      fs.readdirSync('/sys/devices/system/cpu/vulnerabilities').sort(),
+    )
+  }
+})
@@ -1,18 +1,154 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
+/**
+ * A cpu of the owning `SysInfo`.
+ *
+ * It holds a reference to its `SysInfo` and the index of the cpu, values are read from the
+ * `SysInfo` on every call. Handles taken before a `refresh*` call see the refreshed values.
+ */
 export declare class Cpu {
   usage(): number
   name(): string
   name(): string
   /** Cpu frequency in `MHz` */
   frequency(): number
+  /** Whether it's a performance or an efficiency core of a hybrid cpu, `unknown` on other cpus */
+  coreType(): CoreType
   vendorId(): string
   brand(): string
 }
 
+/**
+ * A process of the owning `SysInfo`.
+ *
+ * The values are read from the owning `SysInfo` on every call, so they are updated by its
+ * `refresh*` methods. Once a refresh drops the process, everything but `pid()` throws.
+ */
+export declare class Process {
+  pid(): number
+  /** Parent process id, `null` if the process has no parent */
+  ppid(): number | null
+  name(): string
+  /** Path to the executable, `null` if it can't be read */
+  exe(): string | null
+  cmd(): Array<string>
+  /** Current working directory, `null` if it can't be read */
+  cwd(): string | null
+  /**
+   * Name of the user owning the process, `null` if it's unknown or the owners were not refreshed,
+   * see `ProcessRefreshKind.user`
+   */
+  user(): string | null
+  status(): string
+  /** Start time of the process in seconds since UNIX epoch */
+  startTime(): bigint
+  /** Time the process has been running in seconds */
+  runTime(): bigint
+  /** Cpu usage in percent, it can be over 100% on multi-core systems */
+  cpuUsage(): number
+  /** Resident memory in bytes */
+  memory(): bigint
+  /** Virtual memory in bytes */
+  virtualMemory(): bigint
+  diskUsage(): DiskUsage
+  /** All processes below this one in the process tree, in breadth-first order */
+  descendants(): Array<Process>
+  /** Parent, grandparent and so on up to the root of the process tree */
+  ancestors(): Array<Process>
+  /** Send `signal` to the process, defaults to `Signal.Kill` */
+  kill(signal?: Signal | undefined | null): KillResult
+}
+
 export declare class SysInfo {
-  constructor()
+  /** Gather the given information, or everything if `kinds` is not provided */
+  constructor(kinds?: RefreshKind | undefined | null)
+  /** Same as the constructor, but gathers the information off the main thread */
+  static create(kinds?: RefreshKind | undefined | null): Promise<SysInfo>
+  /**
+   * Same as `refresh`, but runs off the main thread.
+   *
+   * Everything is refreshed if `kinds` is not provided.
+   */
+  refreshAsync(kinds?: RefreshKind | undefined | null): Promise<void>
+  refresh(kinds: RefreshKind): void
+  /**
+   * Sample the system on a background thread every `intervalMs`.
+   *
+   * Snapshots are passed to `callback` when it's provided, otherwise they are delivered through
+   * the returned `Watcher`, which is an async iterator. The watcher refreshes a `System` of its
+   * own, the values of this `SysInfo` are left as they are. Call `stop()` once done with a
+   * callback, it keeps the process alive.
+   */
+  watch(options?: WatchOptions | undefined | null, callback?: ((arg: SystemSnapshot) => void) | undefined | null): Watcher
+  /** Refresh usage and frequency of all cpus */
+  refreshCpu(): void
+  /** Refresh the process list, processes that exited are removed */
+  refreshProcesses(): void
   cpus(): Array<Cpu>
+  /**
+   * Usage of all cpus in percent.
+   *
+   * Like `Cpu.usage()`, it's only meaningful once cpus were refreshed twice at least
+   * `MINIMUM_CPU_UPDATE_INTERVAL` apart, use `sampleCpuUsage` if you don't refresh them yourself.
+   */
+  globalCpuUsage(): number
+  /** Physical cores grouped by package, read from sysfs on Linux and CPUID on x86 */
+  cpuTopology(): Array<CpuPackage>
+  /** Number of physical cores, `null` if it can't be determined */
+  physicalCoreCount(): number | null
+  /** Limits of the cgroup of the current process, `null` outside of Linux or of a cgroup */
+  cgroupLimits(): CgroupLimits | null
+  /**
+   * How many threads can run in parallel once the cpu affinity, the cgroup cpu quota and cpuset
+   * are accounted, use it to size worker pools
+   */
+  effectiveParallelism(): number
+  /** System wide pressure stall information, `null` outside of Linux or when PSI is disabled */
+  pressure(): SystemPressure | null
+  /**
+   * Pressure, memory usage, OOM events and cpu throttling of the cgroup of the current process,
+   * `null` outside of a cgroup v2
+   */
+  cgroupPressure(): CgroupPressure | null
+  /**
+   * Refresh cpu usage twice `intervalMs` apart off the main thread, `globalCpuUsage()` and
+   * `Cpu.usage()` return the sampled values once the promise resolves.
+   *
+   * `intervalMs` is raised to sysinfo's `MINIMUM_CPU_UPDATE_INTERVAL` (200ms on most platforms)
+   * if it's lower or not provided.
+   */
+  sampleCpuUsage(intervalMs?: number | undefined | null): Promise<CpuUsage>
+  /** All processes of the system, sorted by pid */
+  processes(): Array<Process>
+  /** Process with the given pid, `null` if there is no such process */
+  process(pid: number): Process | null
+  /** Processes whose name contains `pattern`, sorted by pid */
+  processesByName(pattern: string, options?: ProcessesByNameOptions | undefined | null): Array<Process>
+  /**
+   * Process tree built from parent links, user threads are left out.
+   *
+   * Returns the tree rooted at `rootPid`, or every tree of the system if it's not provided.
+   * The array is empty if there is no process with the given pid.
+   */
+  processTree(rootPid?: number | undefined | null): Array<ProcessTreeNode>
+  /** The `n` processes with the most disk I/O since the last refresh, busiest first */
+  topProcessesByIo(n: number): Array<Process>
+  /** Send `signal` to the process with the given pid, defaults to `Signal.Kill` */
+  killProcess(pid: number, signal?: Signal | undefined | null): KillResult
+  /** Mounted disks, they are listed on the first call and updated by `refreshDisks` */
+  disks(): Array<Disk>
+  /** Refresh the disks list and their available space */
+  refreshDisks(): void
+  /** Network interfaces sorted by name, they are listed on the first call and updated by `refreshNetworks` */
+  networks(): Array<NetworkInterface>
+  /** Refresh the network interfaces list and their counters */
+  refreshNetworks(): void
+  /** Users of the system, they are listed on the first call and updated by `refreshUsers` */
+  users(): Array<User>
+  /** Groups of the system, they are listed on the first call and updated by `refreshUsers` */
+  groups(): Array<Group>
+  /** Refresh the users and groups lists */
+  refreshUsers(): void
   refreshMemory(): void
   totalMemory(): bigint
   freeMemory(): bigint
@@ -21,7 +157,12 @@ export declare class SysInfo {
   totalSwap(): bigint
   freeSwap(): bigint
   usedSwap(): bigint
-  uptime(): void
+  /** Every `/proc/meminfo` field and the values `free(1)` shows, `null` outside of Linux */
+  memoryDetails(): MemoryDetails | null
+  /** Seconds since boot */
+  uptime(): number
+  /** Uptime with sub-second precision and the idle time, read from `/proc/uptime` on Linux */
+  uptimeDetailed(): Uptime
   bootTime(): bigint
   systemName(): string | null
   longOsVersion(): string | null
@@ -30,9 +171,117 @@ export declare class SysInfo {
   osVersion(): string | null
   distribution(): string
   loadAverage(): LoadAvg
+  /**
+   * Hardware sensors, on Linux `/sys/class/hwmon` and `/sys/class/thermal` are read directly when
+   * the OS reports none
+   */
+  components(options?: ComponentsOptions | undefined | null): Array<Component>
+  /** Refresh the sensors list and their temperatures */
   refreshComponentsList(): void
 }
 
+/**
+ * Handle of a running `SysInfo.watch`, iterate it with `for await` to receive the snapshots.
+ *
+ * A callback keeps the process alive and sampling until `stop()` is called, even once the
+ * `Watcher` is garbage collected. The async iterator stops when the `Watcher` is collected.
+ *
+ * This type implements JavaScript's async iterable protocol.
+ * It can be used with `for await...of` loops.
+ *
+ * @see https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Iteration_protocols#the_async_iterator_and_async_iterable_protocols
+ */
+export declare class Watcher {
+  /** Stop sampling, the async iterator ends once the buffered snapshots are consumed */
+  stop(): void
+  [Symbol.asyncIterator](): AsyncGenerator<SystemSnapshot, void, undefined>
+}
+
+export declare const enum CacheType {
+  Data = 'Data',
+  Instruction = 'Instruction',
+  Unified = 'Unified'
+}
+
+/** Limits of the cgroup of the current process, unset fields are unlimited. */
+export interface CgroupLimits {
+  /** `1` or `2` */
+  version: number
+  /** Memory limit in bytes */
+  memoryLimit?: bigint
+  /** Cpu quota divided by its period, `1.5` means one and a half cores */
+  cpuQuota?: number
+  /** Cpus the cgroup may run on */
+  cpusetCpus?: Array<number>
+  /** Maximum number of processes */
+  pidsLimit?: number
+}
+
+/** Pressure and usage of the cgroup v2 of the current process. */
+export interface CgroupPressure {
+  cpu?: Pressure
+  memory?: Pressure
+  io?: Pressure
+  /** Memory used by the cgroup in bytes */
+  memoryCurrent?: bigint
+  /** Times the memory limit was reached, from `memory.events` */
+  oom?: bigint
+  /** Processes killed by the OOM killer, from `memory.events` */
+  oomKill?: bigint
+  /** Enforcement periods elapsed, from `cpu.stat` */
+  nrPeriods?: bigint
+  /** Periods during which the cgroup was throttled, from `cpu.stat` */
+  nrThrottled?: bigint
+  /** Total throttled time in microseconds, from `cpu.stat` */
+  throttledUsec?: bigint
+}
+
+/** A hardware sensor, temperatures are in degrees Celsius. */
+export interface Component {
+  label: string
+  temperature?: number
+  max?: number
+  critical?: number
+}
+
+export interface ComponentsOptions {
+  /**
+   * Read `sys/class/hwmon` and `sys/class/thermal` under this directory instead of asking the OS,
+   * used to run against a fixture tree.
+   */
+  readRoot?: string
+}
+
+export declare const enum CoreType {
+  Performance = 'performance',
+  Efficiency = 'efficiency',
+  Unknown = 'unknown'
+}
+
+/** A kind of cache, caches of the same shape on several cores are reported once. */
+export interface CpuCache {
+  level: number
+  type: CacheType
+  /** Size in bytes */
+  size: number
+  /** Line size in bytes */
+  lineSize: number
+  /** Ways of associativity, unset for fully associative caches */
+  associativity?: number
+  /** Number of logical cpus sharing one of these caches */
+  sharedBy: number
+}
+
+/** A physical core, `cpus` are the logical cpu numbers of its SMT siblings. */
+export interface CpuCore {
+  /**
+   * Index of the core in its package, by die then by the id the hardware gives it. Hardware ids
+   * have holes on hybrid cpus and repeat across dies and arm64 clusters.
+   */
+  id: number
+  cpus: Array<number>
+}
+
 export declare function cpuFeatures(): CpuFeatures
 
 export interface CpuFeatures {
@@ -42,6 +291,10 @@ export interface CpuFeatures {
   model?: number
   steppingId?: number
   flags: CpuFeaturesFlags
+  /** Only set on x86 */
+  x86Level?: X86Level
+  /** Empty when the cache hierarchy can't be read */
+  caches: Array<CpuCache>
 }
 
 export interface CpuFeaturesFlags {
@@ -88,6 +341,105 @@ export interface CpuFeaturesFlags {
   sm4: boolean
 }
 
+export interface CpuPackage {
+  id: number
+  cores: Array<CpuCore>
+}
+
+export interface CpuRefreshKind {
+  /** Defaults to `false` */
+  usage?: boolean
+  /** Defaults to `false` */
+  frequency?: boolean
+}
+
+export interface CpuSnapshot {
+  name: string
+  /** Cpu usage in percent */
+  usage: number
+  /** Cpu frequency in `MHz` */
+  frequency: number
+}
+
+export interface CpuUsage {
+  /** Usage of all cpus in percent */
+  global: number
+  /** Usage of every cpu in percent, in the same order as `SysInfo.cpus()` */
+  cpus: Array<number>
+}
+
+/**
+ * The kernel report of `/sys/devices/system/cpu/vulnerabilities` on Linux, the mitigations CPUID
+ * advertises on other x86 systems as the `detail` of a `vulnerable` status, empty otherwise
+ */
+export declare function cpuVulnerabilities(): Array<CpuVulnerability>
+
+export interface CpuVulnerability {
+  /** File name under `/sys/devices/system/cpu/vulnerabilities`, like `spectre_v2` or `mds` */
+  name: string
+  status: VulnerabilityStatus
+  /**
+   * What the kernel reports after the status, like `PTI`, or the cpu features a mitigation could
+   * use when read from CPUID
+   */
+  detail?: string
+}
+
+/** A mounted disk, space is in bytes. */
+export interface Disk {
+  name: string
+  mountPoint: string
+  fileSystem: string
+  kind: DiskKind
+  isRemovable: boolean
+  isReadOnly: boolean
+  totalSpace: bigint
+  availableSpace: bigint
+}
+
+export declare const enum DiskKind {
+  HDD = 'HDD',
+  SSD = 'SSD',
+  Unknown = 'Unknown'
+}
+
+/** Disk I/O of a process in bytes */
+export interface DiskUsage {
+  /** Bytes read since the last refresh */
+  readBytes: bigint
+  /** Bytes written since the last refresh */
+  writtenBytes: bigint
+  /** Bytes read since the process started */
+  totalReadBytes: bigint
+  /** Bytes written since the process started */
+  totalWrittenBytes: bigint
+}
+
+export interface Group {
+  gid: number
+  name: string
+}
+
+export interface IpNetwork {
+  addr: string
+  prefix: number
+}
+
+export declare const enum KillFailureReason {
+  /** No process with the given pid exists */
+  NotFound = 'NotFound',
+  /** The signal is not supported on this platform */
+  UnsupportedSignal = 'UnsupportedSignal',
+  /** The signal could not be delivered, e.g. missing permission or the process already exited */
+  Failed = 'Failed'
+}
+
+export interface KillResult {
+  sent: boolean
+  /** Why the signal was not sent, `undefined` if `sent` is `true` */
+  reason?: KillFailureReason
+}
+
 /**
  * A Object representing system load average value.
  *
@@ -109,3 +461,252 @@ export interface LoadAvg {
   /** Average load within fifteen minutes. */
   fifteen: number
 }
+
+/** `/proc/meminfo` and the values `free(1)` derives from it, in bytes. */
+export interface MemoryDetails {
+  /** Every `/proc/meminfo` size by name, converted from `kB` to bytes */
+  fields: Record<string, bigint>
+  /** The unitless `/proc/meminfo` fields by name, the `HugePages_*` page counts */
+  counts: Record<string, bigint>
+  total: bigint
+  /** `total - available`, like procps-ng 4 */
+  used: bigint
+  free: bigint
+  /** `Shmem`, mostly tmpfs */
+  shared: bigint
+  buffers: bigint
+  /** `Cached + SReclaimable` */
+  cache: bigint
+  /** `MemAvailable`, or `free + buffers + cache` on kernels older than 3.14 */
+  available: bigint
+  swapTotal: bigint
+  swapUsed: bigint
+  swapFree: bigint
+}
+
+export interface MemoryRefreshKind {
+  /** Defaults to `false` */
+  ram?: boolean
+  /** Defaults to `false` */
+  swap?: boolean
+}
+
+/** A network interface, the counters without `total` are the deltas since the last refresh. */
+export interface NetworkInterface {
+  name: string
+  macAddress: string
+  ipNetworks: Array<IpNetwork>
+  mtu: bigint
+  /** Received bytes */
+  received: bigint
+  totalReceived: bigint
+  /** Transmitted bytes */
+  transmitted: bigint
+  totalTransmitted: bigint
+  packetsReceived: bigint
+  totalPacketsReceived: bigint
+  packetsTransmitted: bigint
+  totalPacketsTransmitted: bigint
+  errorsOnReceived: bigint
+  totalErrorsOnReceived: bigint
+  errorsOnTransmitted: bigint
+  totalErrorsOnTransmitted: bigint
+}
+
+export interface Pressure {
+  /** Some tasks were stalled */
+  some: PressureStall
+  /** All non idle tasks were stalled at once, unset for the system wide cpu before Linux 5.13 */
+  full?: PressureStall
+}
+
+/** Share of time in percent during which tasks were stalled, averaged over 10, 60 and 300 seconds. */
+export interface PressureStall {
+  avg10: number
+  avg60: number
+  avg300: number
+  /** Total stall time in microseconds */
+  total: bigint
+}
+
+export interface ProcessesByNameOptions {
+  /** Match the whole name instead of a substring of it, defaults to `false` */
+  exact?: boolean
+  /** Also match against the command line, arguments joined by spaces, defaults to `false` */
+  matchCmdline?: boolean
+}
+
+/** The process list, names and parent pids are always refreshed, the rest is opt-in. */
+export interface ProcessRefreshKind {
+  /** Defaults to `false` */
+  cpu?: boolean
+  /** Defaults to `false` */
+  memory?: boolean
+  /** Defaults to `false` */
+  diskUsage?: boolean
+  /** Defaults to `false` */
+  exe?: boolean
+  /** Defaults to `false` */
+  cmd?: boolean
+  /** Defaults to `false` */
+  cwd?: boolean
+  /** Defaults to `false` */
+  root?: boolean
+  /** Defaults to `false` */
+  environ?: boolean
+  /** Defaults to `false` */
+  user?: boolean
+  /** List the threads of the processes on Linux, defaults to `true` */
+  tasks?: boolean
+}
+
+export interface ProcessTreeNode {
+  pid: number
+  name: string
+  /** Child processes, sorted by pid */
+  children: Array<ProcessTreeNode>
+}
+
+/**
+ * Which information to refresh, sections that are not provided are skipped.
+ *
+ * ```js
+ * import { SysInfo } from '@napi-rs/sysinfo';
+ * const s = new SysInfo({ memory: { ram: true } });
+ *
+ * s.refresh({ cpu: { usage: true }, memory: { ram: true, swap: true } })
+ * ```
+ */
+export interface RefreshKind {
+  cpu?: CpuRefreshKind
+  memory?: MemoryRefreshKind
+  processes?: ProcessRefreshKind
+}
+
+/** Signals that can be sent to a process, not every signal is supported on every platform */
+export declare const enum Signal {
+  Hangup = 'Hangup',
+  Interrupt = 'Interrupt',
+  Quit = 'Quit',
+  Illegal = 'Illegal',
+  Trap = 'Trap',
+  Abort = 'Abort',
+  IOT = 'IOT',
+  Bus = 'Bus',
+  FloatingPointException = 'FloatingPointException',
+  Kill = 'Kill',
+  User1 = 'User1',
+  Segv = 'Segv',
+  User2 = 'User2',
+  Pipe = 'Pipe',
+  Alarm = 'Alarm',
+  Term = 'Term',
+  Child = 'Child',
+  Continue = 'Continue',
+  Stop = 'Stop',
+  TSTP = 'TSTP',
+  TTIN = 'TTIN',
+  TTOU = 'TTOU',
+  Urgent = 'Urgent',
+  XCPU = 'XCPU',
+  XFSZ = 'XFSZ',
+  VirtualAlarm = 'VirtualAlarm',
+  Profiling = 'Profiling',
+  Winch = 'Winch',
+  IO = 'IO',
+  Poll = 'Poll',
+  Power = 'Power',
+  Sys = 'Sys'
+}
+
+/**
+ * Pressure stall information of the system, resources are unset when the kernel doesn't report
+ * them.
+ */
+export interface SystemPressure {
+  cpu?: Pressure
+  memory?: Pressure
+  io?: Pressure
+}
+
+/** Values of the system at a point in time, memory is in bytes. */
+export interface SystemSnapshot {
+  /** Milliseconds since UNIX epoch */
+  timestamp: number
+  /** Samples dropped since the previous snapshot because the consumer was too slow */
+  dropped: number
+  globalCpuUsage: number
+  cpus: Array<CpuSnapshot>
+  totalMemory: bigint
+  usedMemory: bigint
+  availableMemory: bigint
+  totalSwap: bigint
+  usedSwap: bigint
+  loadAverage: LoadAvg
+}
+
+export interface Uptime {
+  /** Seconds since boot */
+  seconds: number
+  /** Seconds spent idle, summed over every cpu, only available on Linux */
+  idleSeconds?: number
+  /** Boot time in seconds since UNIX epoch */
+  bootTime: number
+}
+
+export interface User {
+  /** Numeric id on unix, SID on Windows */
+  uid: string
+  /** Primary group id */
+  gid: number
+  name: string
+  /** Groups the user is a member of */
+  groups: Array<Group>
+}
+
+/**
+ * Hypervisor and container the process runs in, from CPUID on x86 and from DMI, `/proc` and the
+ * container engine marker files on Linux
+ */
+export declare function virtualization(): Virtualization
+
+/** Names follow `systemd-detect-virt`. */
+export interface Virtualization {
+  /**
+   * `kvm`, `qemu`, `vmware`, `microsoft`, `xen`, `oracle`, `amazon`, `google`..., unset on bare
+   * metal
+   */
+  hypervisor?: string
+  /** `docker`, `podman`, `kubernetes`, `lxc` or `containerd`, unset outside of containers */
+  container?: string
+  /** Running in the Windows Subsystem for Linux */
+  isWsl: boolean
+}
+
+export declare const enum VulnerabilityStatus {
+  Vulnerable = 'vulnerable',
+  Mitigated = 'mitigated',
+  NotAffected = 'not affected'
+}
+
+export interface WatchOptions {
+  /** Sampling interval, defaults to `1000`. It's raised to `MINIMUM_CPU_UPDATE_INTERVAL` if lower. */
+  intervalMs?: number
+  /** What to refresh before every sample, defaults to cpu usage, cpu frequency and memory */
+  kinds?: RefreshKind
+  /** How many snapshots the async iterator buffers before dropping new ones, defaults to `1` */
+  bufferSize?: number
+}
+
+/** x86-64 psABI microarchitecture level */
+export interface X86Level {
+  /** `1` to `4`, `0` when the baseline `x86-64` features are missing */
+  level: number
+  /** `x86-64-v3` like name, unset at level `0` */
+  name?: string
+  /** Features missing to reach the next level, empty at level `4` */
+  missingForNext: Array<string>
+}
+
+/** The x86-64 psABI level of the cpu, `null` on other architectures */
+export declare function x86MicroarchLevel(): X86Level | null
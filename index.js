@@ -394,5 +394,16 @@ if (!nativeBinding) {
 
 module.exports = nativeBinding
 module.exports.Cpu = nativeBinding.Cpu
+module.exports.Process = nativeBinding.Process
 module.exports.SysInfo = nativeBinding.SysInfo
+module.exports.Watcher = nativeBinding.Watcher
+module.exports.CacheType = nativeBinding.CacheType
+module.exports.CoreType = nativeBinding.CoreType
 module.exports.cpuFeatures = nativeBinding.cpuFeatures
+module.exports.cpuVulnerabilities = nativeBinding.cpuVulnerabilities
+module.exports.DiskKind = nativeBinding.DiskKind
+module.exports.KillFailureReason = nativeBinding.KillFailureReason
+module.exports.Signal = nativeBinding.Signal
+module.exports.virtualization = nativeBinding.virtualization
+module.exports.VulnerabilityStatus = nativeBinding.VulnerabilityStatus
+module.exports.x86MicroarchLevel = nativeBinding.x86MicroarchLevel
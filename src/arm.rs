@@ -0,0 +1,274 @@
+//! ARMv7 feature detection from the ELF auxiliary vector or `/proc/cpuinfo`.
+//!
+//! The parsing is kept free of `cfg(target_arch)` so it can be tested against captured cpuinfo
+//! files on any host.
+
+// Bits of `AT_HWCAP` and `AT_HWCAP2`, from `arch/arm/include/uapi/asm/hwcap.h`
+const HWCAP_VFP: u64 = 1 << 6;
+const HWCAP_NEON: u64 = 1 << 12;
+const HWCAP_VFPV3: u64 = 1 << 13;
+const HWCAP_VFPV4: u64 = 1 << 16;
+const HWCAP_I8MM: u64 = 1 << 27;
+const HWCAP2_AES: u64 = 1 << 0;
+const HWCAP2_PMULL: u64 = 1 << 1;
+const HWCAP2_SHA1: u64 = 1 << 2;
+const HWCAP2_SHA2: u64 = 1 << 3;
+const HWCAP2_CRC32: u64 = 1 << 4;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ArmFeatures {
+  pub neon: bool,
+  pub pmull: bool,
+  pub crc: bool,
+  pub crypto: bool,
+  pub aes: bool,
+  pub sha2: bool,
+  pub i8mm: bool,
+  pub v7: bool,
+  pub vfp2: bool,
+  pub vfp3: bool,
+  pub vfp4: bool,
+}
+
+impl ArmFeatures {
+  /// `platform` is the `AT_PLATFORM` string, `v7l` on an ARMv7 little endian cpu
+  pub(crate) fn from_hwcap(hwcap: u64, hwcap2: u64, platform: Option<&str>) -> Self {
+    let has = |bits: u64, bit: u64| bits & bit != 0;
+    Self::new(
+      Features {
+        neon: has(hwcap, HWCAP_NEON),
+        vfp2: has(hwcap, HWCAP_VFP),
+        vfp3: has(hwcap, HWCAP_VFPV3),
+        vfp4: has(hwcap, HWCAP_VFPV4),
+        i8mm: has(hwcap, HWCAP_I8MM),
+        aes: has(hwcap2, HWCAP2_AES),
+        pmull: has(hwcap2, HWCAP2_PMULL),
+        sha1: has(hwcap2, HWCAP2_SHA1),
+        sha2: has(hwcap2, HWCAP2_SHA2),
+        crc32: has(hwcap2, HWCAP2_CRC32),
+      },
+      platform.and_then(architecture_version),
+    )
+  }
+
+  /// Parse the `Features` line of the first processor in `/proc/cpuinfo`.
+  ///
+  /// Both the 32-bit names and the ones an arm64 kernel prints for its native tasks are
+  /// understood.
+  pub(crate) fn from_cpuinfo(cpuinfo: &str) -> Self {
+    let field = |name: &str| {
+      cpuinfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == name).then(|| value.trim())
+      })
+    };
+    let flags = field("Features")
+      .unwrap_or_default()
+      .split_whitespace()
+      .collect::<Vec<_>>();
+    let has = |name: &str| flags.contains(&name);
+    // arm64 only prints `fp`, which is at least VFPv4 with 32 registers
+    let fp = has("fp");
+    // The architecture in the model name is the reliable one, the ARM1176 of the first Raspberry
+    // Pi reports `CPU architecture: 7` while being an ARMv6.
+    let version = field("model name")
+      .and_then(|model| {
+        let platform = model.rsplit_once('(')?.1.strip_suffix(')')?;
+        architecture_version(platform)
+      })
+      .or_else(|| field("CPU architecture").and_then(|arch| arch.parse().ok()));
+    Self::new(
+      Features {
+        neon: has("neon") || has("asimd"),
+        vfp2: has("vfp") || fp,
+        vfp3: has("vfpv3") || fp,
+        vfp4: has("vfpv4") || fp,
+        i8mm: has("i8mm"),
+        aes: has("aes"),
+        pmull: has("pmull"),
+        sha1: has("sha1"),
+        sha2: has("sha2"),
+        crc32: has("crc32"),
+      },
+      version,
+    )
+  }
+
+  fn new(features: Features, version: Option<u32>) -> Self {
+    let sha2 = features.sha1 && features.sha2;
+    Self {
+      neon: features.neon,
+      pmull: features.pmull,
+      crc: features.crc32,
+      crypto: features.aes && features.pmull && sha2,
+      aes: features.aes,
+      sha2,
+      i8mm: features.i8mm,
+      v7: version.is_some_and(|version| version >= 7),
+      vfp2: features.vfp2,
+      vfp3: features.vfp3,
+      vfp4: features.vfp4,
+    }
+  }
+}
+
+/// Features as reported by the kernel, before they are mapped to the Rust target features
+struct Features {
+  neon: bool,
+  vfp2: bool,
+  vfp3: bool,
+  vfp4: bool,
+  i8mm: bool,
+  aes: bool,
+  pmull: bool,
+  sha1: bool,
+  sha2: bool,
+  crc32: bool,
+}
+
+/// `7` for a `v7l` or `v7b` platform
+fn architecture_version(platform: &str) -> Option<u32> {
+  let digits = platform.strip_prefix('v')?;
+  let end = digits
+    .find(|c: char| !c.is_ascii_digit())
+    .unwrap_or(digits.len());
+  digits[..end].parse().ok()
+}
+
+#[cfg(all(target_arch = "arm", any(target_os = "linux", target_os = "android")))]
+pub(crate) fn detect() -> ArmFeatures {
+  use std::ffi::CStr;
+
+  // SAFETY: `getauxval` has no preconditions, it returns 0 for missing entries and
+  // `AT_PLATFORM` points to a NUL terminated string living as long as the process.
+  let (hwcap, hwcap2, platform) = unsafe {
+    let platform = libc::getauxval(libc::AT_PLATFORM) as *const libc::c_char;
+    (
+      libc::getauxval(libc::AT_HWCAP) as u64,
+      libc::getauxval(libc::AT_HWCAP2) as u64,
+      (!platform.is_null()).then(|| CStr::from_ptr(platform)),
+    )
+  };
+  if hwcap != 0 {
+    return ArmFeatures::from_hwcap(
+      hwcap,
+      hwcap2,
+      platform.and_then(|platform| platform.to_str().ok()),
+    );
+  }
+  std::fs::read_to_string("/proc/cpuinfo")
+    .map(|cpuinfo| ArmFeatures::from_cpuinfo(&cpuinfo))
+    .unwrap_or_default()
+}
+
+/// Only Linux and Android expose the cpu features to user space
+#[cfg(all(
+  target_arch = "arm",
+  not(any(target_os = "linux", target_os = "android"))
+))]
+pub(crate) fn detect() -> ArmFeatures {
+  ArmFeatures::default()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sysfs::fixture;
+
+  fn cpuinfo(name: &str) -> ArmFeatures {
+    let path = fixture(&format!("cpuinfo/{name}.txt"));
+    ArmFeatures::from_cpuinfo(&std::fs::read_to_string(path).unwrap())
+  }
+
+  #[test]
+  fn raspberry_pi_1_is_armv6() {
+    assert_eq!(
+      cpuinfo("raspberry-pi-1"),
+      ArmFeatures {
+        vfp2: true,
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn raspberry_pi_2() {
+    assert_eq!(
+      cpuinfo("raspberry-pi-2"),
+      ArmFeatures {
+        neon: true,
+        v7: true,
+        vfp2: true,
+        vfp3: true,
+        vfp4: true,
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn raspberry_pi_4() {
+    assert_eq!(
+      cpuinfo("raspberry-pi-4"),
+      ArmFeatures {
+        neon: true,
+        crc: true,
+        v7: true,
+        vfp2: true,
+        vfp3: true,
+        vfp4: true,
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn cortex_a53_linux32() {
+    assert_eq!(
+      cpuinfo("cortex-a53-linux32"),
+      ArmFeatures {
+        neon: true,
+        pmull: true,
+        crc: true,
+        crypto: true,
+        aes: true,
+        sha2: true,
+        i8mm: false,
+        v7: true,
+        vfp2: true,
+        vfp3: true,
+        vfp4: true,
+      }
+    );
+  }
+
+  #[test]
+  fn cortex_a72_arm64_kernel() {
+    assert_eq!(
+      cpuinfo("cortex-a72-arm64-kernel"),
+      ArmFeatures {
+        neon: true,
+        crc: true,
+        v7: true,
+        vfp2: true,
+        vfp3: true,
+        vfp4: true,
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn hwcap() {
+    assert_eq!(
+      ArmFeatures::from_hwcap(
+        HWCAP_VFP | HWCAP_NEON | HWCAP_VFPV3 | HWCAP_VFPV4,
+        HWCAP2_AES | HWCAP2_PMULL | HWCAP2_SHA1 | HWCAP2_SHA2 | HWCAP2_CRC32,
+        Some("v8l"),
+      ),
+      cpuinfo("cortex-a53-linux32")
+    );
+    assert!(!ArmFeatures::from_hwcap(HWCAP_VFP, 0, Some("v6l")).v7);
+    assert!(!ArmFeatures::from_hwcap(HWCAP_VFP, 0, None).v7);
+  }
+}
@@ -0,0 +1,177 @@
+use std::path::Path;
+
+use napi_derive::napi;
+
+use crate::sysfs::{cpu_dirs, parse_cpu_list, read_trimmed, sorted_entries};
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CacheType {
+  Data,
+  Instruction,
+  Unified,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A kind of cache, caches of the same shape on several cores are reported once.
+pub struct CpuCache {
+  pub level: u32,
+  #[napi(js_name = "type")]
+  pub cache_type: CacheType,
+  /// Size in bytes
+  pub size: u32,
+  /// Line size in bytes
+  pub line_size: u32,
+  /// Ways of associativity, unset for fully associative caches
+  pub associativity: Option<u32>,
+  /// Number of logical cpus sharing one of these caches
+  pub shared_by: u32,
+}
+
+impl CpuCache {
+  fn sort_key(&self) -> (u32, CacheType, u32) {
+    (self.level, self.cache_type, self.size)
+  }
+}
+
+/// `32K` or `8M` like sizes of `/sys/devices/system/cpu/cpu*/cache/index*/size`
+fn parse_size(size: &str) -> Option<u32> {
+  let (digits, multiplier) = match size.as_bytes().last()? {
+    b'K' => (&size[..size.len() - 1], 1 << 10),
+    b'M' => (&size[..size.len() - 1], 1 << 20),
+    b'G' => (&size[..size.len() - 1], 1 << 30),
+    _ => (size, 1),
+  };
+  digits.parse::<u32>().ok()?.checked_mul(multiplier)
+}
+
+fn read_cache_index(index: &Path) -> Option<CpuCache> {
+  let read = |name: &str| read_trimmed(&index.join(name));
+  let cache_type = match read("type")?.as_str() {
+    "Data" => CacheType::Data,
+    "Instruction" => CacheType::Instruction,
+    "Unified" => CacheType::Unified,
+    _ => return None,
+  };
+  Some(CpuCache {
+    level: read("level")?.parse().ok()?,
+    cache_type,
+    size: parse_size(&read("size")?)?,
+    line_size: read("coherency_line_size")
+      .and_then(|size| size.parse().ok())
+      .unwrap_or_default(),
+    associativity: read("ways_of_associativity")
+      .and_then(|ways| ways.parse().ok())
+      .filter(|ways| *ways != 0),
+    shared_by: read("shared_cpu_list")
+      .map(|list| parse_cpu_list(&list).len() as u32)
+      .unwrap_or(1),
+  })
+}
+
+/// Caches listed under `<root>/sys/devices/system/cpu/cpu*/cache`
+pub(crate) fn read_sysfs(root: &Path) -> Vec<CpuCache> {
+  let mut caches = Vec::<CpuCache>::new();
+  for (_, cpu) in cpu_dirs(root) {
+    for index in sorted_entries(&cpu.join("cache")) {
+      if let Some(cache) = read_cache_index(&index.path()) {
+        if !caches.contains(&cache) {
+          caches.push(cache);
+        }
+      }
+    }
+  }
+  caches.sort_by_key(CpuCache::sort_key);
+  caches
+}
+
+/// Caches described by CPUID leaf 4, or 0x8000001D on AMD
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn read_cpuid() -> Vec<CpuCache> {
+  let Some(parameters) = raw_cpuid::CpuId::new().get_cache_parameters() else {
+    return Vec::new();
+  };
+  let mut caches = parameters
+    .filter_map(|cache| {
+      let cache_type = match cache.cache_type() {
+        raw_cpuid::CacheType::Data => CacheType::Data,
+        raw_cpuid::CacheType::Instruction => CacheType::Instruction,
+        raw_cpuid::CacheType::Unified => CacheType::Unified,
+        _ => return None,
+      };
+      let size = cache.associativity()
+        * cache.physical_line_partitions()
+        * cache.coherency_line_size()
+        * cache.sets();
+      Some(CpuCache {
+        level: cache.level() as u32,
+        cache_type,
+        size: size as u32,
+        line_size: cache.coherency_line_size() as u32,
+        associativity: (!cache.is_fully_associative()).then_some(cache.associativity() as u32),
+        shared_by: cache.max_cores_for_cache() as u32,
+      })
+    })
+    .collect::<Vec<_>>();
+  caches.sort_by_key(CpuCache::sort_key);
+  caches
+}
+
+/// Sysfs describes every core of hybrid cpus, CPUID only the one it runs on, so it's preferred
+pub(crate) fn detect() -> Vec<CpuCache> {
+  let caches = read_sysfs(Path::new("/"));
+  #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+  if caches.is_empty() {
+    return read_cpuid();
+  }
+  caches
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sysfs::fixture;
+
+  fn cache(
+    level: u32,
+    cache_type: CacheType,
+    size: u32,
+    associativity: u32,
+    shared_by: u32,
+  ) -> CpuCache {
+    CpuCache {
+      level,
+      cache_type,
+      size: size * 1024,
+      line_size: 64,
+      associativity: Some(associativity),
+      shared_by,
+    }
+  }
+
+  #[test]
+  fn sizes() {
+    assert_eq!(parse_size("48K"), Some(48 * 1024));
+    assert_eq!(parse_size("8M"), Some(8 * 1024 * 1024));
+    assert_eq!(parse_size("512"), Some(512));
+    assert_eq!(parse_size(""), None);
+  }
+
+  #[test]
+  fn hybrid_fixture() {
+    let root = fixture("sysfs");
+    assert_eq!(
+      read_sysfs(&root),
+      [
+        cache(1, CacheType::Data, 32, 8, 1),
+        cache(1, CacheType::Data, 48, 12, 2),
+        cache(1, CacheType::Instruction, 32, 8, 2),
+        cache(1, CacheType::Instruction, 64, 8, 1),
+        cache(2, CacheType::Unified, 1280, 10, 2),
+        cache(2, CacheType::Unified, 2048, 16, 4),
+        cache(3, CacheType::Unified, 12288, 12, 8),
+      ]
+    );
+  }
+}
@@ -0,0 +1,262 @@
+use std::path::{Path, PathBuf};
+
+use napi_derive::napi;
+
+use crate::sysfs::{parse_cpu_list, read_trimmed};
+
+#[napi(object, object_from_js = false)]
+/// Limits of the cgroup of the current process, unset fields are unlimited.
+pub struct CgroupLimits {
+  /// `1` or `2`
+  pub version: u32,
+  /// Memory limit in bytes
+  pub memory_limit: Option<u64>,
+  /// Cpu quota divided by its period, `1.5` means one and a half cores
+  pub cpu_quota: Option<f64>,
+  /// Cpus the cgroup may run on
+  pub cpuset_cpus: Option<Vec<u32>>,
+  /// Maximum number of processes
+  pub pids_limit: Option<u32>,
+}
+
+/// v1 reports "unlimited" memory as a huge page aligned number instead of `max`
+const V1_UNLIMITED_MEMORY: u64 = 1 << 62;
+
+/// A cgroup directory and the mount point of its hierarchy, limits of the ancestors up to the
+/// mount point apply too.
+pub(crate) struct Cgroup {
+  pub(crate) dir: PathBuf,
+  mount: PathBuf,
+}
+
+impl Cgroup {
+  /// The cgroup directories from the leaf up to the mount point
+  fn ancestors(&self) -> impl Iterator<Item = &Path> {
+    self
+      .dir
+      .ancestors()
+      .take_while(|dir| dir.starts_with(&self.mount))
+  }
+
+  /// The smallest value of `file` in the hierarchy, `parse` returns `None` for "unlimited"
+  fn min<T: PartialOrd>(&self, file: &str, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    self
+      .ancestors()
+      .filter_map(|dir| parse(&read_trimmed(&dir.join(file))?))
+      .reduce(|min, value| if value < min { value } else { min })
+  }
+
+  /// `file` of the closest directory having it
+  fn closest(&self, file: &str) -> Option<String> {
+    self
+      .ancestors()
+      .find_map(|dir| read_trimmed(&dir.join(file)))
+  }
+}
+
+struct Mount {
+  root: String,
+  mount_point: String,
+  fs_type: String,
+  super_options: String,
+}
+
+fn mounts(root: &Path) -> Vec<Mount> {
+  read_trimmed(&root.join("proc/self/mountinfo"))
+    .unwrap_or_default()
+    .lines()
+    .filter_map(|line| {
+      let (mount, fs) = line.split_once(" - ")?;
+      let mut mount = mount.split(' ').skip(3);
+      let mut fs = fs.split(' ');
+      Some(Mount {
+        root: mount.next()?.to_string(),
+        mount_point: mount.next()?.to_string(),
+        fs_type: fs.next()?.to_string(),
+        super_options: fs.nth(1).unwrap_or_default().to_string(),
+      })
+    })
+    .collect()
+}
+
+/// Locate the cgroup of the current process under `root`, `controller` picks the v1 hierarchy.
+///
+/// `/proc/self/cgroup` gives the path from the root of the hierarchy, `/proc/self/mountinfo`
+/// where that hierarchy, or a part of it inside a container, is mounted.
+fn locate(root: &Path, controller: Option<&str>) -> Option<Cgroup> {
+  let cgroups = read_trimmed(&root.join("proc/self/cgroup"))?;
+  let path = cgroups.lines().find_map(|line| {
+    let mut fields = line.splitn(3, ':');
+    let (_, controllers, path) = (fields.next()?, fields.next()?, fields.next()?);
+    let matches = match controller {
+      Some(controller) => controllers.split(',').any(|name| name == controller),
+      None => controllers.is_empty(),
+    };
+    matches.then(|| path.to_string())
+  })?;
+  let mount = mounts(root).into_iter().find(|mount| match controller {
+    Some(controller) => {
+      mount.fs_type == "cgroup"
+        && mount
+          .super_options
+          .split(',')
+          .any(|name| name == controller)
+    }
+    None => mount.fs_type == "cgroup2",
+  })?;
+  let relative = path
+    .strip_prefix(mount.root.as_str())
+    .unwrap_or_default()
+    .trim_start_matches('/');
+  let mount_point = root.join(mount.mount_point.trim_start_matches('/'));
+  Some(Cgroup {
+    dir: mount_point.join(relative),
+    mount: mount_point,
+  })
+}
+
+/// The cgroup v2 of the current process, `None` on cgroup v1 and outside of Linux
+pub(crate) fn locate_v2(root: &Path) -> Option<Cgroup> {
+  let uses_v1 = ["memory", "cpu"]
+    .into_iter()
+    .any(|controller| locate(root, Some(controller)).is_some());
+  if uses_v1 {
+    return None;
+  }
+  locate(root, None)
+}
+
+fn parse_limit<T: std::str::FromStr>(value: &str) -> Option<T> {
+  match value {
+    "max" => None,
+    value => value.parse().ok(),
+  }
+}
+
+/// `cpu.max` holds `$MAX $PERIOD`
+fn parse_cpu_max(value: &str) -> Option<f64> {
+  let (quota, period) = value.split_once(' ')?;
+  let quota = parse_limit::<f64>(quota)?;
+  let period = period.parse::<f64>().ok().filter(|period| *period > 0.0)?;
+  Some(quota / period)
+}
+
+fn read_v2(cgroup: &Cgroup) -> CgroupLimits {
+  CgroupLimits {
+    version: 2,
+    memory_limit: cgroup.min("memory.max", parse_limit),
+    cpu_quota: cgroup.min("cpu.max", parse_cpu_max),
+    cpuset_cpus: cgroup
+      .closest("cpuset.cpus.effective")
+      .map(|cpus| parse_cpu_list(&cpus)),
+    pids_limit: cgroup.min("pids.max", parse_limit),
+  }
+}
+
+fn read_v1(root: &Path) -> CgroupLimits {
+  let memory = locate(root, Some("memory"));
+  let cpu = locate(root, Some("cpu"));
+  let cpuset = locate(root, Some("cpuset"));
+  let pids = locate(root, Some("pids"));
+  CgroupLimits {
+    version: 1,
+    memory_limit: memory.and_then(|cgroup| {
+      cgroup.min("memory.limit_in_bytes", |limit| {
+        limit
+          .parse::<u64>()
+          .ok()
+          .filter(|limit| *limit < V1_UNLIMITED_MEMORY)
+      })
+    }),
+    cpu_quota: cpu.and_then(|cgroup| {
+      cgroup
+        .ancestors()
+        .filter_map(|dir| {
+          let quota = read_trimmed(&dir.join("cpu.cfs_quota_us"))?
+            .parse::<f64>()
+            .ok()?;
+          let period = read_trimmed(&dir.join("cpu.cfs_period_us"))?
+            .parse::<f64>()
+            .ok()?;
+          // `-1` when unlimited
+          (quota > 0.0 && period > 0.0).then_some(quota / period)
+        })
+        .reduce(f64::min)
+    }),
+    cpuset_cpus: cpuset.and_then(|cgroup| {
+      cgroup
+        .closest("cpuset.effective_cpus")
+        .or_else(|| cgroup.closest("cpuset.cpus"))
+        .map(|cpus| parse_cpu_list(&cpus))
+    }),
+    pids_limit: pids.and_then(|cgroup| cgroup.min("pids.max", parse_limit)),
+  }
+}
+
+/// Limits of the cgroup of the current process
+pub(crate) fn read(root: &Path) -> Option<CgroupLimits> {
+  if let Some(cgroup) = locate_v2(root) {
+    return Some(read_v2(&cgroup));
+  }
+  ["memory", "cpu", "cpuset", "pids"]
+    .into_iter()
+    .any(|controller| locate(root, Some(controller)).is_some())
+    .then(|| read_v1(root))
+}
+
+/// How many threads can run in parallel, `available` is what the OS allows, usually
+/// `std::thread::available_parallelism`
+pub(crate) fn effective_parallelism(available: usize, limits: Option<&CgroupLimits>) -> u32 {
+  let mut parallelism = available;
+  if let Some(limits) = limits {
+    if let Some(quota) = limits.cpu_quota {
+      parallelism = parallelism.min(quota.ceil() as usize);
+    }
+    if let Some(cpus) = limits.cpuset_cpus.as_ref().filter(|cpus| !cpus.is_empty()) {
+      parallelism = parallelism.min(cpus.len());
+    }
+  }
+  parallelism.max(1) as u32
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sysfs::fixture;
+
+  #[test]
+  fn v2() {
+    let limits = read(&fixture("cgroup-v2")).unwrap();
+    assert_eq!(limits.version, 2);
+    // The pod limit is lower than the container one
+    assert_eq!(limits.memory_limit, Some(512 * 1024 * 1024));
+    assert_eq!(limits.cpu_quota, Some(1.5));
+    assert_eq!(limits.cpuset_cpus, Some(vec![0, 1, 2, 3]));
+    assert_eq!(limits.pids_limit, Some(1024));
+    assert_eq!(effective_parallelism(64, Some(&limits)), 2);
+  }
+
+  #[test]
+  fn v1() {
+    let limits = read(&fixture("cgroup-v1")).unwrap();
+    assert_eq!(limits.version, 1);
+    assert_eq!(limits.memory_limit, Some(256 * 1024 * 1024));
+    assert_eq!(limits.cpu_quota, Some(0.5));
+    assert_eq!(limits.cpuset_cpus, Some(vec![2, 3]));
+    assert_eq!(limits.pids_limit, None);
+    assert_eq!(effective_parallelism(64, Some(&limits)), 1);
+  }
+
+  #[test]
+  fn no_cgroup() {
+    assert!(read(&fixture("nonexistent")).is_none());
+    assert_eq!(effective_parallelism(8, None), 8);
+  }
+
+  #[test]
+  fn cpu_max() {
+    assert_eq!(parse_cpu_max("max 100000"), None);
+    assert_eq!(parse_cpu_max("250000 100000"), Some(2.5));
+    assert_eq!(parse_cpu_max("100000 0"), None);
+  }
+}
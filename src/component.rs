@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use napi_derive::napi;
+
+use crate::sysfs::{read_trimmed, sorted_entries};
+
+#[napi(object)]
+pub struct ComponentsOptions {
+  /// Read `sys/class/hwmon` and `sys/class/thermal` under this directory instead of asking the OS,
+  /// used to run against a fixture tree.
+  pub read_root: Option<String>,
+}
+
+#[napi(object, object_from_js = false)]
+/// A hardware sensor, temperatures are in degrees Celsius.
+pub struct Component {
+  pub label: String,
+  pub temperature: Option<f64>,
+  pub max: Option<f64>,
+  pub critical: Option<f64>,
+}
+
+impl From<&sysinfo::Component> for Component {
+  fn from(value: &sysinfo::Component) -> Self {
+    Self {
+      label: value.label().to_string(),
+      temperature: value.temperature().map(f64::from),
+      max: value.max().map(f64::from),
+      critical: value.critical().map(f64::from),
+    }
+  }
+}
+
+/// Sysfs temperatures are in millidegrees Celsius
+fn read_millidegrees(path: &Path) -> Option<f64> {
+  read_trimmed(path)?
+    .parse::<i64>()
+    .ok()
+    .map(|value| value as f64 / 1000.0)
+}
+
+/// `tempN_input` files of a `hwmon` device, labelled `<name> <tempN_label>` like `sensors` does
+fn hwmon_components(device: &Path) -> Vec<Component> {
+  let name = read_trimmed(&device.join("name")).unwrap_or_default();
+  sorted_entries(device)
+    .into_iter()
+    .filter_map(|entry| {
+      let file_name = entry.file_name();
+      let sensor = file_name.to_str()?.strip_suffix("_input")?;
+      if !sensor.starts_with("temp") {
+        return None;
+      }
+      let label =
+        read_trimmed(&device.join(format!("{sensor}_label"))).unwrap_or_else(|| sensor.to_string());
+      Some(Component {
+        label: format!("{name} {label}").trim().to_string(),
+        temperature: read_millidegrees(&entry.path()),
+        max: read_millidegrees(&device.join(format!("{sensor}_max"))),
+        critical: read_millidegrees(&device.join(format!("{sensor}_crit"))),
+      })
+    })
+    .collect()
+}
+
+/// A `thermal_zone`, its critical temperature is the trip point of type `critical`
+fn thermal_zone_component(zone: &Path) -> Option<Component> {
+  let label = read_trimmed(&zone.join("type"))?;
+  let critical = (0..)
+    .map_while(|index| {
+      read_trimmed(&zone.join(format!("trip_point_{index}_type"))).map(|kind| (index, kind))
+    })
+    .find(|(_, kind)| kind == "critical")
+    .and_then(|(index, _)| read_millidegrees(&zone.join(format!("trip_point_{index}_temp"))));
+  Some(Component {
+    label,
+    temperature: read_millidegrees(&zone.join("temp")),
+    max: None,
+    critical,
+  })
+}
+
+/// Read the sensors from `<root>/sys/class/hwmon` and `<root>/sys/class/thermal`
+pub(crate) fn read_sysfs(root: &Path) -> Vec<Component> {
+  let class = root.join("sys/class");
+  let hwmon = sorted_entries(&class.join("hwmon"))
+    .into_iter()
+    .flat_map(|device| hwmon_components(&device.path()));
+  let thermal = sorted_entries(&class.join("thermal"))
+    .into_iter()
+    .filter(|zone| {
+      zone
+        .file_name()
+        .to_string_lossy()
+        .starts_with("thermal_zone")
+    })
+    .filter_map(|zone| thermal_zone_component(&zone.path()));
+  hwmon.chain(thermal).collect()
+}
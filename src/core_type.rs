@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use napi_derive::napi;
+
+use crate::sysfs::{parse_cpu_list, read_trimmed};
+
+#[napi(string_enum = "lowercase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreType {
+  Performance,
+  Efficiency,
+  Unknown,
+}
+
+/// Hybrid cpus register a `cpu_core` and a `cpu_atom` PMU listing their cpus, since Linux 5.13
+pub(crate) fn read_sysfs(root: &Path) -> HashMap<u32, CoreType> {
+  let devices = root.join("sys/devices");
+  [
+    ("cpu_core", CoreType::Performance),
+    ("cpu_atom", CoreType::Efficiency),
+  ]
+  .into_iter()
+  .flat_map(|(pmu, core_type)| {
+    read_trimmed(&devices.join(pmu).join("cpus"))
+      .map(|cpus| parse_cpu_list(&cpus))
+      .unwrap_or_default()
+      .into_iter()
+      .map(move |cpu| (cpu, core_type))
+  })
+  .collect()
+}
+
+/// CPUID leaf 0x1A of the cpu running the calling thread
+#[cfg(all(windows, any(target_arch = "x86", target_arch = "x86_64")))]
+fn current_core_type() -> CoreType {
+  match raw_cpuid::cpuid!(0x1A, 0).eax >> 24 {
+    0x20 => CoreType::Efficiency,
+    0x40 => CoreType::Performance,
+    _ => CoreType::Unknown,
+  }
+}
+
+/// Run `read` on every cpu by moving a thread from one to another, cpus the thread can't be moved
+/// to are left out
+#[cfg(all(windows, any(target_arch = "x86", target_arch = "x86_64")))]
+pub(crate) fn on_every_cpu<T: Send + 'static>(read: fn() -> T) -> Vec<(u32, T)> {
+  use windows_sys::Win32::System::Threading::{GetCurrentThread, SetThreadAffinityMask};
+
+  let logical = std::thread::available_parallelism()
+    .map_or(1, |count| count.get())
+    .min(usize::BITS as usize);
+  std::thread::spawn(move || {
+    (0..logical)
+      .filter_map(|cpu| {
+        // SAFETY: the pseudo handle of the current thread is always valid
+        let previous = unsafe { SetThreadAffinityMask(GetCurrentThread(), 1 << cpu) };
+        if previous == 0 {
+          return None;
+        }
+        // Let the scheduler move the thread before asking the cpu
+        std::thread::yield_now();
+        Some((cpu as u32, read()))
+      })
+      .collect()
+  })
+  .join()
+  .unwrap_or_default()
+}
+
+/// Run CPUID leaf 0x1A on every cpu
+#[cfg(all(windows, any(target_arch = "x86", target_arch = "x86_64")))]
+fn read_cpuid() -> HashMap<u32, CoreType> {
+  let max_leaf = raw_cpuid::cpuid!(0, 0).eax;
+  // CPUID.07H:EDX[15] is set on hybrid parts
+  if max_leaf < 0x1A || raw_cpuid::cpuid!(7, 0).edx & (1 << 15) == 0 {
+    return HashMap::new();
+  }
+  on_every_cpu(current_core_type).into_iter().collect()
+}
+
+fn core_types() -> &'static HashMap<u32, CoreType> {
+  static CORE_TYPES: OnceLock<HashMap<u32, CoreType>> = OnceLock::new();
+  CORE_TYPES.get_or_init(|| {
+    #[allow(unused_mut)]
+    let mut core_types = read_sysfs(Path::new("/"));
+    #[cfg(all(windows, any(target_arch = "x86", target_arch = "x86_64")))]
+    if core_types.is_empty() {
+      core_types = read_cpuid();
+    }
+    #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
+    if let Ok(clusters) = crate::cpu::EFFICIENCY_CLUSTER_TYPE.as_ref() {
+      core_types.extend(clusters);
+    }
+    core_types
+  })
+}
+
+/// Core type of the `cpu`th logical cpu, `Unknown` if the cpu isn't hybrid
+pub(crate) fn lookup(cpu: u32) -> CoreType {
+  core_types().get(&cpu).copied().unwrap_or(CoreType::Unknown)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sysfs::fixture;
+
+  #[test]
+  fn hybrid_fixture() {
+    let root = fixture("sysfs");
+    let core_types = read_sysfs(&root);
+    assert_eq!(core_types.len(), 8);
+    assert!((0..4).all(|cpu| core_types[&cpu] == CoreType::Performance));
+    assert!((4..8).all(|cpu| core_types[&cpu] == CoreType::Efficiency));
+  }
+
+  #[test]
+  fn not_hybrid() {
+    assert!(read_sysfs(Path::new("/nonexistent")).is_empty());
+  }
+}
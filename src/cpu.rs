@@ -1,7 +1,15 @@
+#[cfg(all(target_arch = "aarch64", target_os = "macos"))]
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use napi::{bindgen_prelude::Reference, Env, Task};
 use napi_derive::napi;
 #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
 use once_cell::sync::Lazy;
 
+use crate::{core_type::CoreType, task::SharedSystem, SysInfo};
+
 #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
 /// (efficiency, performance)
 static CORE_FREQUENCY: Lazy<napi::Result<(u64, u64)>> = Lazy::new(|| unsafe {
@@ -117,7 +125,7 @@ static CORE_FREQUENCY: Lazy<napi::Result<(u64, u64)>> = Lazy::new(|| unsafe {
 });
 
 #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
-static EFFICIENCY_CLUSTER_TYPE: Lazy<napi::Result<std::collections::HashMap<u32, CpuClusterType>>> =
+pub(crate) static EFFICIENCY_CLUSTER_TYPE: Lazy<napi::Result<HashMap<u32, CoreType>>> =
   Lazy::new(|| unsafe {
     use std::collections::HashMap;
 
@@ -199,9 +207,9 @@ static EFFICIENCY_CLUSTER_TYPE: Lazy<napi::Result<std::collections::HashMap<u32,
               cluster_types.insert(
                 cpu_id,
                 match cluster_type {
-                  "E\0" => CpuClusterType::Efficiency,
-                  "P\0" => CpuClusterType::Performance,
-                  _ => CpuClusterType::Unknown,
+                  "E\0" => CoreType::Efficiency,
+                  "P\0" => CoreType::Performance,
+                  _ => CoreType::Unknown,
                 },
               );
             }
@@ -222,40 +230,73 @@ fn pre_init() {
   let _ = &*EFFICIENCY_CLUSTER_TYPE;
 }
 
-#[cfg(all(target_arch = "aarch64", target_os = "macos"))]
-enum CpuClusterType {
-  Efficiency,
-  Performance,
-  Unknown,
-}
-
 #[napi]
+/// A cpu of the owning `SysInfo`.
+///
+/// It holds a reference to its `SysInfo` and the index of the cpu, values are read from the
+/// `SysInfo` on every call. Handles taken before a `refresh*` call see the refreshed values.
 pub struct Cpu {
-  pub(crate) inner: &'static sysinfo::Cpu,
+  sys: Reference<SysInfo>,
+  index: usize,
+}
+
+impl Cpu {
+  pub(crate) fn new(env: Env, sys: &Reference<SysInfo>, index: usize) -> napi::Result<Self> {
+    Ok(Self {
+      sys: sys.clone(env)?,
+      index,
+    })
+  }
+
+  fn with_inner<T>(&self, f: impl FnOnce(&sysinfo::Cpu) -> T) -> napi::Result<T> {
+    let system = self.sys.system();
+    let cpu = system.cpus().get(self.index).ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Cpu {} no longer exists", self.index),
+      )
+    })?;
+    Ok(f(cpu))
+  }
+
+  /// Number of the cpu as the OS knows it, parsed from its name
+  fn logical_number(&self, name: &str) -> u32 {
+    // Names count from 1 on macOS
+    #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
+    if let Ok(n) = name.parse::<u32>() {
+      return n.saturating_sub(1);
+    }
+    name
+      .strip_prefix("cpu")
+      .and_then(|n| n.parse().ok())
+      .unwrap_or(self.index as u32)
+  }
 }
 
 #[napi]
 impl Cpu {
   #[napi]
-  pub fn usage(&self) -> f32 {
-    self.inner.cpu_usage()
+  pub fn usage(&self) -> napi::Result<f32> {
+    self.with_inner(|cpu| cpu.cpu_usage())
   }
 
   #[cfg(not(all(target_arch = "aarch64", target_os = "macos")))]
   #[napi]
-  pub fn name(&self) -> String {
-    self.inner.name().to_string()
+  pub fn name(&self) -> napi::Result<String> {
+    self.with_inner(|cpu| cpu.name().to_string())
   }
 
   #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
   #[napi]
-  pub fn name(&self) -> String {
-    let name = self.inner.name();
-    if let Ok(n) = name.parse::<u32>() {
-      format!("cpu{}", if n != 0 { n - 1 } else { 0 })
-    } else {
-      name.to_string()
-    }
+  pub fn name(&self) -> napi::Result<String> {
+    self.with_inner(|cpu| {
+      let name = cpu.name();
+      if let Ok(n) = name.parse::<u32>() {
+        format!("cpu{}", if n != 0 { n - 1 } else { 0 })
+      } else {
+        name.to_string()
+      }
+    })
   }
 
   #[napi]
@@ -263,7 +304,7 @@ impl Cpu {
   pub fn frequency(&self) -> napi::Result<u32> {
     #[cfg(not(all(target_arch = "aarch64", target_os = "macos")))]
     {
-      Ok(self.inner.frequency() as u32)
+      self.with_inner(|cpu| cpu.frequency() as u32)
     }
     #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
     {
@@ -276,25 +317,88 @@ impl Cpu {
         .as_ref()
         .map_err(|err| Error::new(err.status, err.reason.clone()))?;
 
-      if let Ok(c) = self.inner.name().parse::<u32>() {
-        match efficiency_cluster_type.get(&(if c == 0 { 0 } else { c - 1 })) {
-          Some(CpuClusterType::Efficiency) => Ok((*efficiency_core_frequency) as u32),
-          Some(CpuClusterType::Performance) => Ok((*performance_core_frequency) as u32),
-          _ => Ok(self.inner.frequency() as u32),
+      self.with_inner(|cpu| {
+        if let Ok(c) = cpu.name().parse::<u32>() {
+          match efficiency_cluster_type.get(&(if c == 0 { 0 } else { c - 1 })) {
+            Some(CoreType::Efficiency) => (*efficiency_core_frequency) as u32,
+            Some(CoreType::Performance) => (*performance_core_frequency) as u32,
+            _ => cpu.frequency() as u32,
+          }
+        } else {
+          cpu.frequency() as u32
         }
-      } else {
-        Ok(self.inner.frequency() as u32)
-      }
+      })
     }
   }
 
   #[napi]
-  pub fn vendor_id(&self) -> String {
-    self.inner.vendor_id().to_string()
+  /// Whether it's a performance or an efficiency core of a hybrid cpu, `unknown` on other cpus
+  pub fn core_type(&self) -> napi::Result<CoreType> {
+    self.with_inner(|cpu| crate::core_type::lookup(self.logical_number(cpu.name())))
+  }
+
+  #[napi]
+  pub fn vendor_id(&self) -> napi::Result<String> {
+    self.with_inner(|cpu| cpu.vendor_id().to_string())
   }
 
   #[napi]
-  pub fn brand(&self) -> String {
-    self.inner.brand().to_string()
+  pub fn brand(&self) -> napi::Result<String> {
+    self.with_inner(|cpu| cpu.brand().to_string())
+  }
+}
+
+#[napi(object)]
+pub struct CpuUsage {
+  /// Usage of all cpus in percent
+  pub global: f64,
+  /// Usage of every cpu in percent, in the same order as `SysInfo.cpus()`
+  pub cpus: Vec<f64>,
+}
+
+/// Refresh cpu usage of the `System` of a `SysInfo` twice off the main thread, then swap it in so
+/// its getters see the sampled values
+pub struct SampleCpuUsage {
+  system: Arc<SharedSystem>,
+  interval: Duration,
+}
+
+impl SampleCpuUsage {
+  pub(crate) fn new(system: Arc<SharedSystem>, interval_ms: Option<u32>) -> Self {
+    Self {
+      system,
+      interval: interval_ms
+        .map(|ms| Duration::from_millis(ms as u64))
+        .unwrap_or_default()
+        .max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL),
+    }
+  }
+}
+
+impl Task for SampleCpuUsage {
+  type Output = CpuUsage;
+  type JsValue = CpuUsage;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    let interval = self.interval;
+    let kinds =
+      sysinfo::RefreshKind::nothing().with_cpu(sysinfo::CpuRefreshKind::nothing().with_cpu_usage());
+    let usage = self.system.refresh_and_swap(kinds, |system| {
+      std::thread::sleep(interval);
+      system.refresh_cpu_usage();
+      CpuUsage {
+        global: system.global_cpu_usage() as f64,
+        cpus: system
+          .cpus()
+          .iter()
+          .map(|cpu| cpu.cpu_usage() as f64)
+          .collect(),
+      }
+    });
+    Ok(usage)
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
   }
 }
@@ -0,0 +1,47 @@
+use napi_derive::napi;
+
+#[napi(string_enum)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum DiskKind {
+  HDD,
+  SSD,
+  Unknown,
+}
+
+impl From<sysinfo::DiskKind> for DiskKind {
+  fn from(value: sysinfo::DiskKind) -> Self {
+    match value {
+      sysinfo::DiskKind::HDD => Self::HDD,
+      sysinfo::DiskKind::SSD => Self::SSD,
+      sysinfo::DiskKind::Unknown(_) => Self::Unknown,
+    }
+  }
+}
+
+#[napi(object, object_from_js = false)]
+/// A mounted disk, space is in bytes.
+pub struct Disk {
+  pub name: String,
+  pub mount_point: String,
+  pub file_system: String,
+  pub kind: DiskKind,
+  pub is_removable: bool,
+  pub is_read_only: bool,
+  pub total_space: u64,
+  pub available_space: u64,
+}
+
+impl From<&sysinfo::Disk> for Disk {
+  fn from(value: &sysinfo::Disk) -> Self {
+    Self {
+      name: value.name().to_string_lossy().into_owned(),
+      mount_point: value.mount_point().to_string_lossy().into_owned(),
+      file_system: value.file_system().to_string_lossy().into_owned(),
+      kind: value.kind().into(),
+      is_removable: value.is_removable(),
+      is_read_only: value.is_read_only(),
+      total_space: value.total_space(),
+      available_space: value.available_space(),
+    }
+  }
+}
@@ -1,12 +1,53 @@
 #![deny(clippy::all)]
 
-use cpu::Cpu;
-use napi::{bindgen_prelude::Reference, Env, Result};
-
+use cache::CpuCache;
+use cgroup::CgroupLimits;
+use component::{Component, ComponentsOptions};
+use cpu::{Cpu, SampleCpuUsage};
+use disk::Disk;
+use std::sync::{Arc, OnceLock, RwLockReadGuard};
+
+use napi::{
+  bindgen_prelude::{AsyncTask, Reference},
+  Env, Result,
+};
+
+use memory::MemoryDetails;
 use napi_derive::napi;
-
+use network::NetworkInterface;
+use pressure::{CgroupPressure, SystemPressure};
+use process::{KillResult, Process, ProcessTreeNode, ProcessesByNameOptions, Signal};
+use refresh::RefreshKind;
+use task::{CreateSysInfo, Refresh, SharedSystem};
+use topology::CpuPackage;
+use user::{Group, User};
+use virtualization::Virtualization;
+use vulnerability::CpuVulnerability;
+use watch::{WatchCallback, WatchOptions, Watcher};
+
+#[cfg(any(target_arch = "arm", test))]
+mod arm;
+mod cache;
+mod cgroup;
+mod component;
+mod core_type;
 mod cpu;
+mod disk;
+mod memory;
+mod network;
+mod pressure;
+mod process;
+mod refresh;
 mod sys;
+mod sysfs;
+mod task;
+mod topology;
+mod user;
+mod virtualization;
+mod vulnerability;
+mod watch;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", test))]
+mod x86;
 
 #[napi(object)]
 pub struct CpuFeatures {
@@ -16,6 +57,21 @@ pub struct CpuFeatures {
   pub model: Option<u32>,
   pub stepping_id: Option<u32>,
   pub flags: CpuFeaturesFlags,
+  /// Only set on x86
+  pub x86_level: Option<X86Level>,
+  /// Empty when the cache hierarchy can't be read
+  pub caches: Vec<CpuCache>,
+}
+
+#[napi(object)]
+/// x86-64 psABI microarchitecture level
+pub struct X86Level {
+  /// `1` to `4`, `0` when the baseline `x86-64` features are missing
+  pub level: u32,
+  /// `x86-64-v3` like name, unset at level `0`
+  pub name: Option<String>,
+  /// Features missing to reach the next level, empty at level `4`
+  pub missing_for_next: Vec<String>,
 }
 
 #[napi(object)]
@@ -90,6 +146,25 @@ pub struct CpuFeaturesFlags {
   pub vfp4: bool,
 }
 
+#[cfg(target_arch = "arm")]
+impl From<arm::ArmFeatures> for CpuFeaturesFlags {
+  fn from(value: arm::ArmFeatures) -> Self {
+    Self {
+      neon: value.neon,
+      pmull: value.pmull,
+      crc: value.crc,
+      crypto: value.crypto,
+      aes: value.aes,
+      sha2: value.sha2,
+      i8mm: value.i8mm,
+      v7: value.v7,
+      vfp2: value.vfp2,
+      vfp3: value.vfp3,
+      vfp4: value.vfp4,
+    }
+  }
+}
+
 #[napi(object)]
 #[cfg(target_arch = "aarch64")]
 pub struct CpuFeaturesFlags {
@@ -199,19 +274,9 @@ pub fn cpu_features() -> CpuFeatures {
       sm4: is_aarch64_feature_detected!("sm4"),
     },
     #[cfg(target_arch = "arm")]
-    flags: CpuFeaturesFlags {
-      neon: false,
-      pmull: false,
-      crc: false,
-      crypto: false,
-      aes: false,
-      sha2: false,
-      i8mm: false,
-      v7: false,
-      vfp2: false,
-      vfp3: false,
-      vfp4: false,
-    },
+    flags: arm::detect().into(),
+    x86_level: None,
+    caches: cache::detect(),
   }
 }
 
@@ -287,9 +352,34 @@ pub fn cpu_features() -> CpuFeatures {
       adx: is_x86_feature_detected!("adx"),
       rtm: is_x86_feature_detected!("rtm"),
     },
+    x86_level: Some(x86::detect()),
+    caches: cache::detect(),
   }
 }
 
+#[napi]
+/// The x86-64 psABI level of the cpu, `null` on other architectures
+pub fn x86_microarch_level() -> Option<X86Level> {
+  #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+  return Some(x86::detect());
+  #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+  None
+}
+
+#[napi]
+/// Hypervisor and container the process runs in, from CPUID on x86 and from DMI, `/proc` and the
+/// container engine marker files on Linux
+pub fn virtualization() -> Virtualization {
+  virtualization::detect(std::path::Path::new("/"))
+}
+
+#[napi]
+/// The kernel report of `/sys/devices/system/cpu/vulnerabilities` on Linux, the mitigations CPUID
+/// advertises on other x86 systems as the `detail` of a `vulnerable` status, empty otherwise
+pub fn cpu_vulnerabilities() -> Vec<CpuVulnerability> {
+  vulnerability::detect()
+}
+
 #[napi(object)]
 /// A Object representing system load average value.
 ///
@@ -321,69 +411,426 @@ impl From<sysinfo::LoadAvg> for LoadAvg {
   }
 }
 
+#[napi(object)]
+pub struct Uptime {
+  /// Seconds since boot
+  pub seconds: f64,
+  /// Seconds spent idle, summed over every cpu, only available on Linux
+  pub idle_seconds: Option<f64>,
+  /// Boot time in seconds since UNIX epoch
+  pub boot_time: f64,
+}
+
+impl Uptime {
+  #[cfg(target_os = "linux")]
+  fn read() -> Self {
+    let parsed = std::fs::read_to_string("/proc/uptime")
+      .ok()
+      .and_then(|content| {
+        let mut fields = content
+          .split_whitespace()
+          .map(|field| field.parse::<f64>().ok());
+        Some((fields.next()??, fields.next().flatten()))
+      });
+    match parsed {
+      Some((seconds, idle_seconds)) => Self {
+        seconds,
+        idle_seconds,
+        boot_time: sysinfo::System::boot_time() as f64,
+      },
+      None => Self::from_sysinfo(),
+    }
+  }
+
+  #[cfg(not(target_os = "linux"))]
+  fn read() -> Self {
+    Self::from_sysinfo()
+  }
+
+  fn from_sysinfo() -> Self {
+    Self {
+      seconds: sysinfo::System::uptime() as f64,
+      idle_seconds: None,
+      boot_time: sysinfo::System::boot_time() as f64,
+    }
+  }
+}
+
 #[napi]
 pub struct SysInfo {
-  system: sysinfo::System,
+  system: Arc<SharedSystem>,
+  /// Loaded on first use, listing disks has to query every mount point
+  disks: Option<sysinfo::Disks>,
+  /// Loaded on first use, the deltas are relative to the previous `refreshNetworks`
+  networks: Option<sysinfo::Networks>,
+  /// Loaded on first use and updated by `refreshComponentsList`
+  components: Option<sysinfo::Components>,
+  /// Loaded on first use, `Process.user` resolves the owners from it
+  users: OnceLock<sysinfo::Users>,
+  /// Loaded on first use
+  groups: Option<sysinfo::Groups>,
 }
 
-#[napi]
 impl SysInfo {
-  #[napi(constructor)]
-  pub fn new() -> Self {
+  pub(crate) fn with_system(system: SharedSystem) -> Self {
     Self {
-      system: sysinfo::System::new_with_specifics(sysinfo::RefreshKind::everything()),
+      system: Arc::new(system),
+      disks: None,
+      networks: None,
+      components: None,
+      users: OnceLock::new(),
+      groups: None,
     }
   }
 
+  pub(crate) fn system(&self) -> RwLockReadGuard<'_, sysinfo::System> {
+    self.system.read()
+  }
+
+  pub(crate) fn user_list(&self) -> &sysinfo::Users {
+    self
+      .users
+      .get_or_init(sysinfo::Users::new_with_refreshed_list)
+  }
+}
+
+#[napi]
+impl SysInfo {
+  #[napi(constructor)]
+  /// Gather the given information, or everything if `kinds` is not provided
+  pub fn new(kinds: Option<RefreshKind>) -> Self {
+    Self::with_system(SharedSystem::new(
+      kinds
+        .map(Into::into)
+        .unwrap_or_else(sysinfo::RefreshKind::everything),
+    ))
+  }
+
+  #[napi(ts_return_type = "Promise<SysInfo>")]
+  /// Same as the constructor, but gathers the information off the main thread
+  pub fn create(kinds: Option<RefreshKind>) -> AsyncTask<CreateSysInfo> {
+    AsyncTask::new(CreateSysInfo::new(kinds.map(Into::into)))
+  }
+
+  #[napi(ts_return_type = "Promise<void>")]
+  /// Same as `refresh`, but runs off the main thread.
+  ///
+  /// Everything is refreshed if `kinds` is not provided.
+  pub fn refresh_async(&self, kinds: Option<RefreshKind>) -> AsyncTask<Refresh> {
+    AsyncTask::new(Refresh::new(self.system.clone(), kinds.map(Into::into)))
+  }
+
+  #[napi]
+  pub fn refresh(&mut self, kinds: RefreshKind) {
+    self.system.refresh(kinds.into());
+  }
+
+  #[napi]
+  /// Sample the system on a background thread every `intervalMs`.
+  ///
+  /// Snapshots are passed to `callback` when it's provided, otherwise they are delivered through
+  /// the returned `Watcher`, which is an async iterator. The watcher refreshes a `System` of its
+  /// own, the values of this `SysInfo` are left as they are. Call `stop()` once done with a
+  /// callback, it keeps the process alive.
+  pub fn watch(
+    &self,
+    options: Option<WatchOptions>,
+    #[napi(ts_arg_type = "((arg: SystemSnapshot) => void) | undefined | null")] callback: Option<
+      WatchCallback,
+    >,
+  ) -> Result<Watcher> {
+    Watcher::spawn(options, callback)
+  }
+
+  #[napi]
+  /// Refresh usage and frequency of all cpus
+  pub fn refresh_cpu(&mut self) {
+    self
+      .system
+      .refresh(sysinfo::RefreshKind::nothing().with_cpu(sysinfo::CpuRefreshKind::everything()));
+  }
+
+  #[napi]
+  /// Refresh the process list, processes that exited are removed
+  pub fn refresh_processes(&mut self) {
+    // What `System::refresh_processes` refreshes
+    self.system.refresh(
+      sysinfo::RefreshKind::nothing().with_processes(
+        sysinfo::ProcessRefreshKind::nothing()
+          .with_memory()
+          .with_cpu()
+          .with_disk_usage()
+          .with_exe(sysinfo::UpdateKind::OnlyIfNotSet)
+          .with_tasks(),
+      ),
+    );
+  }
+
   #[napi]
   pub fn cpus(&self, env: Env, this: Reference<SysInfo>) -> Result<Vec<Cpu>> {
-    let cpus = this.share_with(env, |sys| Ok(sys.system.cpus()))?;
-    Ok(cpus.iter().map(|inner| Cpu { inner }).collect())
+    (0..self.system().cpus().len())
+      .map(|index| Cpu::new(env, &this, index))
+      .collect()
+  }
+
+  #[napi]
+  /// Usage of all cpus in percent.
+  ///
+  /// Like `Cpu.usage()`, it's only meaningful once cpus were refreshed twice at least
+  /// `MINIMUM_CPU_UPDATE_INTERVAL` apart, use `sampleCpuUsage` if you don't refresh them yourself.
+  pub fn global_cpu_usage(&self) -> f32 {
+    self.system().global_cpu_usage()
+  }
+
+  #[napi]
+  /// Physical cores grouped by package, read from sysfs on Linux and CPUID on x86
+  pub fn cpu_topology(&self) -> Vec<CpuPackage> {
+    let logical = match self.system().cpus().len() {
+      0 => std::thread::available_parallelism().map_or(1, |count| count.get()),
+      count => count,
+    };
+    topology::detect(logical as u32)
+  }
+
+  #[napi]
+  /// Number of physical cores, `null` if it can't be determined
+  pub fn physical_core_count(&self) -> Option<u32> {
+    sysinfo::System::physical_core_count().map(|count| count as u32)
+  }
+
+  #[napi]
+  /// Limits of the cgroup of the current process, `null` outside of Linux or of a cgroup
+  pub fn cgroup_limits(&self) -> Option<CgroupLimits> {
+    cgroup::read(std::path::Path::new("/"))
+  }
+
+  #[napi]
+  /// How many threads can run in parallel once the cpu affinity, the cgroup cpu quota and cpuset
+  /// are accounted, use it to size worker pools
+  pub fn effective_parallelism(&self) -> u32 {
+    let available = std::thread::available_parallelism().map_or(1, |count| count.get());
+    cgroup::effective_parallelism(available, cgroup::read(std::path::Path::new("/")).as_ref())
+  }
+
+  #[napi]
+  /// System wide pressure stall information, `null` outside of Linux or when PSI is disabled
+  pub fn pressure(&self) -> Option<SystemPressure> {
+    pressure::read(std::path::Path::new("/"))
+  }
+
+  #[napi]
+  /// Pressure, memory usage, OOM events and cpu throttling of the cgroup of the current process,
+  /// `null` outside of a cgroup v2
+  pub fn cgroup_pressure(&self) -> Option<CgroupPressure> {
+    pressure::read_cgroup(std::path::Path::new("/"))
+  }
+
+  #[napi(ts_return_type = "Promise<CpuUsage>")]
+  /// Refresh cpu usage twice `intervalMs` apart off the main thread, `globalCpuUsage()` and
+  /// `Cpu.usage()` return the sampled values once the promise resolves.
+  ///
+  /// `intervalMs` is raised to sysinfo's `MINIMUM_CPU_UPDATE_INTERVAL` (200ms on most platforms)
+  /// if it's lower or not provided.
+  pub fn sample_cpu_usage(&self, interval_ms: Option<u32>) -> AsyncTask<SampleCpuUsage> {
+    AsyncTask::new(SampleCpuUsage::new(self.system.clone(), interval_ms))
+  }
+
+  #[napi]
+  /// All processes of the system, sorted by pid
+  pub fn processes(&self, env: Env, this: Reference<SysInfo>) -> Result<Vec<Process>> {
+    process::sorted_by_pid(env, &this, process::pids(&this.system()))
+  }
+
+  #[napi]
+  /// Process with the given pid, `null` if there is no such process
+  pub fn process(&self, env: Env, this: Reference<SysInfo>, pid: u32) -> Result<Option<Process>> {
+    this
+      .system()
+      .process(sysinfo::Pid::from_u32(pid))
+      .map(|process| Process::new(env, &this, process.pid()))
+      .transpose()
+  }
+
+  #[napi]
+  /// Processes whose name contains `pattern`, sorted by pid
+  pub fn processes_by_name(
+    &self,
+    env: Env,
+    this: Reference<SysInfo>,
+    pattern: String,
+    options: Option<ProcessesByNameOptions>,
+  ) -> Result<Vec<Process>> {
+    let pids = process::find_by_name(&this.system(), &pattern, options.unwrap_or_default())
+      .into_iter()
+      .map(|process| process.pid())
+      .collect::<Vec<_>>();
+    process::sorted_by_pid(env, &this, pids)
+  }
+
+  #[napi]
+  /// Process tree built from parent links, user threads are left out.
+  ///
+  /// Returns the tree rooted at `rootPid`, or every tree of the system if it's not provided.
+  /// The array is empty if there is no process with the given pid.
+  pub fn process_tree(&self, root_pid: Option<u32>) -> Vec<ProcessTreeNode> {
+    process::tree(&self.system(), root_pid.map(sysinfo::Pid::from_u32))
+  }
+
+  #[napi]
+  /// The `n` processes with the most disk I/O since the last refresh, busiest first
+  pub fn top_processes_by_io(
+    &self,
+    env: Env,
+    this: Reference<SysInfo>,
+    n: u32,
+  ) -> Result<Vec<Process>> {
+    process::top_by_io(&this.system(), n as usize)
+      .into_iter()
+      .map(|process| Process::new(env, &this, process.pid()))
+      .collect()
+  }
+
+  #[napi]
+  /// Send `signal` to the process with the given pid, defaults to `Signal.Kill`
+  pub fn kill_process(&self, pid: u32, signal: Option<Signal>) -> KillResult {
+    self
+      .system()
+      .process(sysinfo::Pid::from_u32(pid))
+      .map(|process| process::kill(process, signal))
+      .unwrap_or_else(KillResult::not_found)
+  }
+
+  #[napi]
+  /// Mounted disks, they are listed on the first call and updated by `refreshDisks`
+  pub fn disks(&mut self) -> Vec<Disk> {
+    self
+      .disks
+      .get_or_insert_with(sysinfo::Disks::new_with_refreshed_list)
+      .list()
+      .iter()
+      .map(Disk::from)
+      .collect()
+  }
+
+  #[napi]
+  /// Refresh the disks list and their available space
+  pub fn refresh_disks(&mut self) {
+    match &mut self.disks {
+      Some(disks) => disks.refresh(true),
+      None => self.disks = Some(sysinfo::Disks::new_with_refreshed_list()),
+    }
+  }
+
+  #[napi]
+  /// Network interfaces sorted by name, they are listed on the first call and updated by `refreshNetworks`
+  pub fn networks(&mut self) -> Vec<NetworkInterface> {
+    let networks = self
+      .networks
+      .get_or_insert_with(sysinfo::Networks::new_with_refreshed_list);
+    let mut interfaces = networks
+      .iter()
+      .map(|(name, data)| NetworkInterface::new(name, data))
+      .collect::<Vec<_>>();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+  }
+
+  #[napi]
+  /// Refresh the network interfaces list and their counters
+  pub fn refresh_networks(&mut self) {
+    match &mut self.networks {
+      Some(networks) => networks.refresh(true),
+      None => self.networks = Some(sysinfo::Networks::new_with_refreshed_list()),
+    }
+  }
+
+  #[napi]
+  /// Users of the system, they are listed on the first call and updated by `refreshUsers`
+  pub fn users(&self) -> Vec<User> {
+    self.user_list().list().iter().map(User::from).collect()
+  }
+
+  #[napi]
+  /// Groups of the system, they are listed on the first call and updated by `refreshUsers`
+  pub fn groups(&mut self) -> Vec<Group> {
+    self
+      .groups
+      .get_or_insert_with(sysinfo::Groups::new_with_refreshed_list)
+      .list()
+      .iter()
+      .map(Group::from)
+      .collect()
+  }
+
+  #[napi]
+  /// Refresh the users and groups lists
+  pub fn refresh_users(&mut self) {
+    if let Some(users) = self.users.get_mut() {
+      users.refresh();
+    }
+    if let Some(groups) = &mut self.groups {
+      groups.refresh();
+    }
   }
 
   #[napi]
   pub fn refresh_memory(&mut self) {
-    self.system.refresh_memory();
+    self.system.refresh(
+      sysinfo::RefreshKind::nothing().with_memory(sysinfo::MemoryRefreshKind::everything()),
+    );
   }
 
   #[napi]
   pub fn total_memory(&self) -> u64 {
-    self.system.total_memory()
+    self.system().total_memory()
   }
 
   #[napi]
   pub fn free_memory(&self) -> u64 {
-    self.system.free_memory()
+    self.system().free_memory()
   }
 
   #[napi]
   pub fn available_memory(&self) -> u64 {
-    self.system.available_memory()
+    self.system().available_memory()
   }
 
   #[napi]
   pub fn used_memory(&self) -> u64 {
-    self.system.used_memory()
+    self.system().used_memory()
   }
 
   #[napi]
   pub fn total_swap(&self) -> u64 {
-    self.system.total_swap()
+    self.system().total_swap()
   }
 
   #[napi]
   pub fn free_swap(&self) -> u64 {
-    self.system.free_swap()
+    self.system().free_swap()
   }
 
   #[napi]
   pub fn used_swap(&self) -> u64 {
-    self.system.used_swap()
+    self.system().used_swap()
   }
 
   #[napi]
-  pub fn uptime(&self) {
-    sysinfo::System::uptime();
+  /// Every `/proc/meminfo` field and the values `free(1)` shows, `null` outside of Linux
+  pub fn memory_details(&self) -> Option<MemoryDetails> {
+    memory::read(std::path::Path::new("/"))
+  }
+
+  #[napi]
+  /// Seconds since boot
+  pub fn uptime(&self) -> f64 {
+    sysinfo::System::uptime() as f64
+  }
+
+  #[napi]
+  /// Uptime with sub-second precision and the idle time, read from `/proc/uptime` on Linux
+  pub fn uptime_detailed(&self) -> Uptime {
+    Uptime::read()
   }
 
   #[napi]
@@ -427,7 +874,32 @@ impl SysInfo {
   }
 
   #[napi]
+  /// Hardware sensors, on Linux `/sys/class/hwmon` and `/sys/class/thermal` are read directly when
+  /// the OS reports none
+  pub fn components(&mut self, options: Option<ComponentsOptions>) -> Vec<Component> {
+    if let Some(root) = options.and_then(|options| options.read_root) {
+      return component::read_sysfs(std::path::Path::new(&root));
+    }
+    let components = self
+      .components
+      .get_or_insert_with(sysinfo::Components::new_with_refreshed_list)
+      .list()
+      .iter()
+      .map(Component::from)
+      .collect::<Vec<_>>();
+    #[cfg(target_os = "linux")]
+    if components.is_empty() {
+      return component::read_sysfs(std::path::Path::new("/"));
+    }
+    components
+  }
+
+  #[napi]
+  /// Refresh the sensors list and their temperatures
   pub fn refresh_components_list(&mut self) {
-    self.system.refresh_all();
+    match &mut self.components {
+      Some(components) => components.refresh(true),
+      None => self.components = Some(sysinfo::Components::new_with_refreshed_list()),
+    }
   }
 }
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use napi_derive::napi;
+
+use crate::sysfs::read_trimmed;
+
+#[napi(object, object_from_js = false)]
+/// `/proc/meminfo` and the values `free(1)` derives from it, in bytes.
+pub struct MemoryDetails {
+  /// Every `/proc/meminfo` size by name, converted from `kB` to bytes
+  pub fields: HashMap<String, u64>,
+  /// The unitless `/proc/meminfo` fields by name, the `HugePages_*` page counts
+  pub counts: HashMap<String, u64>,
+  pub total: u64,
+  /// `total - available`, like procps-ng 4
+  pub used: u64,
+  pub free: u64,
+  /// `Shmem`, mostly tmpfs
+  pub shared: u64,
+  pub buffers: u64,
+  /// `Cached + SReclaimable`
+  pub cache: u64,
+  /// `MemAvailable`, or `free + buffers + cache` on kernels older than 3.14
+  pub available: u64,
+  pub swap_total: u64,
+  pub swap_used: u64,
+  pub swap_free: u64,
+}
+
+/// The sizes and the unitless counts of `/proc/meminfo`
+fn parse_meminfo(content: &str) -> (HashMap<String, u64>, HashMap<String, u64>) {
+  let mut fields = HashMap::new();
+  let mut counts = HashMap::new();
+  for line in content.lines() {
+    let Some((name, value)) = line.split_once(':') else {
+      continue;
+    };
+    let mut value = value.split_whitespace();
+    let Some(number) = value.next().and_then(|number| number.parse::<u64>().ok()) else {
+      continue;
+    };
+    let name = name.trim().to_string();
+    match value.next() {
+      Some("kB") => fields.insert(name, number * 1024),
+      _ => counts.insert(name, number),
+    };
+  }
+  (fields, counts)
+}
+
+impl MemoryDetails {
+  fn new(fields: HashMap<String, u64>, counts: HashMap<String, u64>) -> Self {
+    let field = |name: &str| fields.get(name).copied().unwrap_or_default();
+    let total = field("MemTotal");
+    let free = field("MemFree");
+    let buffers = field("Buffers");
+    let cache = field("Cached") + field("SReclaimable");
+    let available = fields
+      .get("MemAvailable")
+      .copied()
+      .unwrap_or(free + buffers + cache)
+      .min(total);
+    let swap_total = field("SwapTotal");
+    let swap_free = field("SwapFree");
+    Self {
+      total,
+      used: total - available,
+      free,
+      shared: field("Shmem"),
+      buffers,
+      cache,
+      available,
+      swap_total,
+      swap_used: swap_total.saturating_sub(swap_free),
+      swap_free,
+      fields,
+      counts,
+    }
+  }
+}
+
+/// `None` outside of Linux
+pub(crate) fn read(root: &Path) -> Option<MemoryDetails> {
+  let (fields, counts) = parse_meminfo(&read_trimmed(&root.join("proc/meminfo"))?);
+  (!fields.is_empty()).then(|| MemoryDetails::new(fields, counts))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sysfs::fixture;
+
+  #[test]
+  fn meminfo_fixture() {
+    let meminfo = std::fs::read_to_string(fixture("meminfo.txt")).unwrap();
+    let (fields, counts) = parse_meminfo(&meminfo);
+    let details = MemoryDetails::new(fields, counts);
+    let kib = |kib: u64| kib * 1024;
+    assert_eq!(details.fields["Dirty"], kib(86056));
+    assert_eq!(details.fields["Hugepagesize"], kib(2048));
+    assert_eq!(details.counts["HugePages_Total"], 16);
+    assert!(!details.fields.contains_key("HugePages_Total"));
+    assert_eq!(details.total, kib(6158152));
+    assert_eq!(details.used, kib(6158152 - 5524452));
+    assert_eq!(details.cache, kib(2910460 + 87636));
+    assert_eq!(details.shared, kib(9288));
+    assert_eq!(details.swap_used, kib(2097148 - 1048572));
+  }
+
+  #[test]
+  fn without_mem_available() {
+    let (fields, counts) = parse_meminfo(
+      "MemTotal: 1000 kB\nMemFree: 100 kB\nBuffers: 50 kB\nCached: 200 kB\nSReclaimable: 25 kB\n",
+    );
+    let details = MemoryDetails::new(fields, counts);
+    assert_eq!(details.available, 375 * 1024);
+    assert_eq!(details.used, 625 * 1024);
+  }
+}
@@ -0,0 +1,60 @@
+use napi_derive::napi;
+
+#[napi(object, object_from_js = false)]
+pub struct IpNetwork {
+  pub addr: String,
+  pub prefix: u32,
+}
+
+#[napi(object, object_from_js = false)]
+/// A network interface, the counters without `total` are the deltas since the last refresh.
+pub struct NetworkInterface {
+  pub name: String,
+  pub mac_address: String,
+  pub ip_networks: Vec<IpNetwork>,
+  pub mtu: u64,
+  /// Received bytes
+  pub received: u64,
+  pub total_received: u64,
+  /// Transmitted bytes
+  pub transmitted: u64,
+  pub total_transmitted: u64,
+  pub packets_received: u64,
+  pub total_packets_received: u64,
+  pub packets_transmitted: u64,
+  pub total_packets_transmitted: u64,
+  pub errors_on_received: u64,
+  pub total_errors_on_received: u64,
+  pub errors_on_transmitted: u64,
+  pub total_errors_on_transmitted: u64,
+}
+
+impl NetworkInterface {
+  pub(crate) fn new(name: &str, data: &sysinfo::NetworkData) -> Self {
+    Self {
+      name: name.to_string(),
+      mac_address: data.mac_address().to_string(),
+      ip_networks: data
+        .ip_networks()
+        .iter()
+        .map(|network| IpNetwork {
+          addr: network.addr.to_string(),
+          prefix: network.prefix as u32,
+        })
+        .collect(),
+      mtu: data.mtu(),
+      received: data.received(),
+      total_received: data.total_received(),
+      transmitted: data.transmitted(),
+      total_transmitted: data.total_transmitted(),
+      packets_received: data.packets_received(),
+      total_packets_received: data.total_packets_received(),
+      packets_transmitted: data.packets_transmitted(),
+      total_packets_transmitted: data.total_packets_transmitted(),
+      errors_on_received: data.errors_on_received(),
+      total_errors_on_received: data.total_errors_on_received(),
+      errors_on_transmitted: data.errors_on_transmitted(),
+      total_errors_on_transmitted: data.total_errors_on_transmitted(),
+    }
+  }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use napi_derive::napi;
+
+use crate::cgroup;
+use crate::sysfs::read_trimmed;
+
+#[napi(object, object_from_js = false)]
+#[derive(Debug, PartialEq)]
+/// Share of time in percent during which tasks were stalled, averaged over 10, 60 and 300 seconds.
+pub struct PressureStall {
+  pub avg10: f64,
+  pub avg60: f64,
+  pub avg300: f64,
+  /// Total stall time in microseconds
+  pub total: u64,
+}
+
+#[napi(object, object_from_js = false)]
+#[derive(Debug, PartialEq)]
+pub struct Pressure {
+  /// Some tasks were stalled
+  pub some: PressureStall,
+  /// All non idle tasks were stalled at once, unset for the system wide cpu before Linux 5.13
+  pub full: Option<PressureStall>,
+}
+
+#[napi(object, object_from_js = false)]
+/// Pressure stall information of the system, resources are unset when the kernel doesn't report
+/// them.
+pub struct SystemPressure {
+  pub cpu: Option<Pressure>,
+  pub memory: Option<Pressure>,
+  pub io: Option<Pressure>,
+}
+
+#[napi(object, object_from_js = false)]
+/// Pressure and usage of the cgroup v2 of the current process.
+pub struct CgroupPressure {
+  pub cpu: Option<Pressure>,
+  pub memory: Option<Pressure>,
+  pub io: Option<Pressure>,
+  /// Memory used by the cgroup in bytes
+  pub memory_current: Option<u64>,
+  /// Times the memory limit was reached, from `memory.events`
+  pub oom: Option<u64>,
+  /// Processes killed by the OOM killer, from `memory.events`
+  pub oom_kill: Option<u64>,
+  /// Enforcement periods elapsed, from `cpu.stat`
+  pub nr_periods: Option<u64>,
+  /// Periods during which the cgroup was throttled, from `cpu.stat`
+  pub nr_throttled: Option<u64>,
+  /// Total throttled time in microseconds, from `cpu.stat`
+  pub throttled_usec: Option<u64>,
+}
+
+/// Parse a `some avg10=0.00 avg60=0.00 avg300=0.00 total=0` line, without its `some`/`full` prefix
+fn parse_stall(fields: &str) -> Option<PressureStall> {
+  let values = fields
+    .split_whitespace()
+    .filter_map(|field| field.split_once('='))
+    .collect::<HashMap<_, _>>();
+  Some(PressureStall {
+    avg10: values.get("avg10")?.parse().ok()?,
+    avg60: values.get("avg60")?.parse().ok()?,
+    avg300: values.get("avg300")?.parse().ok()?,
+    total: values.get("total")?.parse().ok()?,
+  })
+}
+
+/// Parse the content of `/proc/pressure/*` or of a `*.pressure` cgroup file
+fn parse_pressure(content: &str) -> Option<Pressure> {
+  let line = |kind: &str| {
+    content
+      .lines()
+      .find_map(|line| line.strip_prefix(kind)?.strip_prefix(' '))
+      .and_then(parse_stall)
+  };
+  Some(Pressure {
+    some: line("some")?,
+    full: line("full"),
+  })
+}
+
+fn read_pressure(path: &Path) -> Option<Pressure> {
+  parse_pressure(&read_trimmed(path)?)
+}
+
+/// Values of a flat keyed file like `memory.events` or `cpu.stat`
+fn read_keyed(path: &Path) -> HashMap<String, u64> {
+  read_trimmed(path)
+    .unwrap_or_default()
+    .lines()
+    .filter_map(|line| {
+      let (key, value) = line.split_once(' ')?;
+      Some((key.to_string(), value.trim().parse().ok()?))
+    })
+    .collect()
+}
+
+/// `None` if the kernel doesn't expose PSI
+pub(crate) fn read(root: &Path) -> Option<SystemPressure> {
+  let dir = root.join("proc/pressure");
+  let pressure = SystemPressure {
+    cpu: read_pressure(&dir.join("cpu")),
+    memory: read_pressure(&dir.join("memory")),
+    io: read_pressure(&dir.join("io")),
+  };
+  (pressure.cpu.is_some() || pressure.memory.is_some() || pressure.io.is_some()).then_some(pressure)
+}
+
+/// `None` outside of a cgroup v2
+pub(crate) fn read_cgroup(root: &Path) -> Option<CgroupPressure> {
+  let dir = cgroup::locate_v2(root)?.dir;
+  let events = read_keyed(&dir.join("memory.events"));
+  let stat = read_keyed(&dir.join("cpu.stat"));
+  Some(CgroupPressure {
+    cpu: read_pressure(&dir.join("cpu.pressure")),
+    memory: read_pressure(&dir.join("memory.pressure")),
+    io: read_pressure(&dir.join("io.pressure")),
+    memory_current: read_trimmed(&dir.join("memory.current")).and_then(|value| value.parse().ok()),
+    oom: events.get("oom").copied(),
+    oom_kill: events.get("oom_kill").copied(),
+    nr_periods: stat.get("nr_periods").copied(),
+    nr_throttled: stat.get("nr_throttled").copied(),
+    throttled_usec: stat.get("throttled_usec").copied(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sysfs::fixture;
+
+  fn stall(avg10: f64, avg60: f64, avg300: f64, total: u64) -> PressureStall {
+    PressureStall {
+      avg10,
+      avg60,
+      avg300,
+      total,
+    }
+  }
+
+  #[test]
+  fn without_full() {
+    assert_eq!(
+      parse_pressure("some avg10=0.10 avg60=0.20 avg300=0.30 total=42"),
+      Some(Pressure {
+        some: stall(0.1, 0.2, 0.3, 42),
+        full: None,
+      })
+    );
+    assert_eq!(parse_pressure(""), None);
+  }
+
+  #[test]
+  fn system() {
+    let pressure = read(&fixture("cgroup-v2")).unwrap();
+    assert_eq!(
+      pressure.cpu,
+      Some(Pressure {
+        some: stall(1.52, 0.87, 0.25, 123456789),
+        full: Some(stall(0.0, 0.0, 0.0, 0)),
+      })
+    );
+    assert_eq!(
+      pressure.io.unwrap().full,
+      Some(stall(2.5, 1.5, 0.75, 876543))
+    );
+    assert!(read(Path::new("/nonexistent")).is_none());
+  }
+
+  #[test]
+  fn cgroup() {
+    let pressure = read_cgroup(&fixture("cgroup-v2")).unwrap();
+    assert_eq!(
+      pressure.cpu,
+      Some(Pressure {
+        some: stall(12.5, 8.25, 3.0, 5000000),
+        full: Some(stall(10.0, 6.0, 2.0, 4000000)),
+      })
+    );
+    assert!(pressure.io.is_none());
+    assert_eq!(pressure.memory_current, Some(402653184));
+    assert_eq!(pressure.oom, Some(2));
+    assert_eq!(pressure.oom_kill, Some(1));
+    assert_eq!(pressure.nr_periods, Some(1200));
+    assert_eq!(pressure.nr_throttled, Some(300));
+    assert_eq!(pressure.throttled_usec, Some(4500000));
+  }
+}
@@ -0,0 +1,504 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ffi::OsStr;
+
+use napi::{bindgen_prelude::Reference, Env, Error, Result, Status};
+use napi_derive::napi;
+
+use crate::SysInfo;
+
+#[napi(object)]
+#[derive(Default)]
+pub struct ProcessesByNameOptions {
+  /// Match the whole name instead of a substring of it, defaults to `false`
+  pub exact: Option<bool>,
+  /// Also match against the command line, arguments joined by spaces, defaults to `false`
+  pub match_cmdline: Option<bool>,
+}
+
+#[napi(string_enum)]
+/// Signals that can be sent to a process, not every signal is supported on every platform
+#[allow(clippy::upper_case_acronyms)]
+pub enum Signal {
+  Hangup,
+  Interrupt,
+  Quit,
+  Illegal,
+  Trap,
+  Abort,
+  IOT,
+  Bus,
+  FloatingPointException,
+  Kill,
+  User1,
+  Segv,
+  User2,
+  Pipe,
+  Alarm,
+  Term,
+  Child,
+  Continue,
+  Stop,
+  TSTP,
+  TTIN,
+  TTOU,
+  Urgent,
+  XCPU,
+  XFSZ,
+  VirtualAlarm,
+  Profiling,
+  Winch,
+  IO,
+  Poll,
+  Power,
+  Sys,
+}
+
+impl From<Signal> for sysinfo::Signal {
+  fn from(value: Signal) -> Self {
+    match value {
+      Signal::Hangup => Self::Hangup,
+      Signal::Interrupt => Self::Interrupt,
+      Signal::Quit => Self::Quit,
+      Signal::Illegal => Self::Illegal,
+      Signal::Trap => Self::Trap,
+      Signal::Abort => Self::Abort,
+      Signal::IOT => Self::IOT,
+      Signal::Bus => Self::Bus,
+      Signal::FloatingPointException => Self::FloatingPointException,
+      Signal::Kill => Self::Kill,
+      Signal::User1 => Self::User1,
+      Signal::Segv => Self::Segv,
+      Signal::User2 => Self::User2,
+      Signal::Pipe => Self::Pipe,
+      Signal::Alarm => Self::Alarm,
+      Signal::Term => Self::Term,
+      Signal::Child => Self::Child,
+      Signal::Continue => Self::Continue,
+      Signal::Stop => Self::Stop,
+      Signal::TSTP => Self::TSTP,
+      Signal::TTIN => Self::TTIN,
+      Signal::TTOU => Self::TTOU,
+      Signal::Urgent => Self::Urgent,
+      Signal::XCPU => Self::XCPU,
+      Signal::XFSZ => Self::XFSZ,
+      Signal::VirtualAlarm => Self::VirtualAlarm,
+      Signal::Profiling => Self::Profiling,
+      Signal::Winch => Self::Winch,
+      Signal::IO => Self::IO,
+      Signal::Poll => Self::Poll,
+      Signal::Power => Self::Power,
+      Signal::Sys => Self::Sys,
+    }
+  }
+}
+
+#[napi(string_enum)]
+pub enum KillFailureReason {
+  /// No process with the given pid exists
+  NotFound,
+  /// The signal is not supported on this platform
+  UnsupportedSignal,
+  /// The signal could not be delivered, e.g. missing permission or the process already exited
+  Failed,
+}
+
+#[napi(object)]
+pub struct KillResult {
+  pub sent: bool,
+  /// Why the signal was not sent, `undefined` if `sent` is `true`
+  pub reason: Option<KillFailureReason>,
+}
+
+impl KillResult {
+  pub(crate) fn not_found() -> Self {
+    Self {
+      sent: false,
+      reason: Some(KillFailureReason::NotFound),
+    }
+  }
+}
+
+/// Send `signal` to `process`, `Signal.Kill` if it's not provided
+pub(crate) fn kill(process: &sysinfo::Process, signal: Option<Signal>) -> KillResult {
+  match process.kill_with(signal.unwrap_or(Signal::Kill).into()) {
+    Some(true) => KillResult {
+      sent: true,
+      reason: None,
+    },
+    Some(false) => KillResult {
+      sent: false,
+      reason: Some(KillFailureReason::Failed),
+    },
+    None => KillResult {
+      sent: false,
+      reason: Some(KillFailureReason::UnsupportedSignal),
+    },
+  }
+}
+
+#[napi(object)]
+pub struct ProcessTreeNode {
+  pub pid: u32,
+  pub name: String,
+  /// Child processes, sorted by pid
+  pub children: Vec<ProcessTreeNode>,
+}
+
+#[napi(object, object_from_js = false)]
+/// Disk I/O of a process in bytes
+pub struct DiskUsage {
+  /// Bytes read since the last refresh
+  pub read_bytes: u64,
+  /// Bytes written since the last refresh
+  pub written_bytes: u64,
+  /// Bytes read since the process started
+  pub total_read_bytes: u64,
+  /// Bytes written since the process started
+  pub total_written_bytes: u64,
+}
+
+impl From<sysinfo::DiskUsage> for DiskUsage {
+  fn from(value: sysinfo::DiskUsage) -> Self {
+    Self {
+      read_bytes: value.read_bytes,
+      written_bytes: value.written_bytes,
+      total_read_bytes: value.total_read_bytes,
+      total_written_bytes: value.total_written_bytes,
+    }
+  }
+}
+
+#[napi]
+/// A process of the owning `SysInfo`.
+///
+/// The values are read from the owning `SysInfo` on every call, so they are updated by its
+/// `refresh*` methods. Once a refresh drops the process, everything but `pid()` throws.
+pub struct Process {
+  sys: Reference<SysInfo>,
+  pid: sysinfo::Pid,
+}
+
+impl Process {
+  pub(crate) fn new(env: Env, sys: &Reference<SysInfo>, pid: sysinfo::Pid) -> Result<Self> {
+    Ok(Self {
+      sys: sys.clone(env)?,
+      pid,
+    })
+  }
+
+  fn not_found(&self) -> Error {
+    Error::new(
+      Status::GenericFailure,
+      format!("Process {} no longer exists", self.pid),
+    )
+  }
+
+  fn with_inner<T>(&self, f: impl FnOnce(&sysinfo::Process) -> T) -> Result<T> {
+    let system = self.sys.system();
+    let process = system.process(self.pid).ok_or_else(|| self.not_found())?;
+    Ok(f(process))
+  }
+}
+
+/// Wrap the pids into `Process` and sort them
+pub(crate) fn sorted_by_pid(
+  env: Env,
+  sys: &Reference<SysInfo>,
+  pids: impl IntoIterator<Item = sysinfo::Pid>,
+) -> Result<Vec<Process>> {
+  let mut pids = pids.into_iter().collect::<Vec<_>>();
+  pids.sort_unstable();
+  pids
+    .into_iter()
+    .map(|pid| Process::new(env, sys, pid))
+    .collect()
+}
+
+/// Threads are listed as processes on Linux, but their usage is already accounted in the process
+/// they belong to, so they are left out of every list.
+fn is_thread(process: &sysinfo::Process) -> bool {
+  matches!(process.thread_kind(), Some(sysinfo::ThreadKind::Userland))
+}
+
+/// Pids of every process, without the threads
+pub(crate) fn pids(system: &sysinfo::System) -> Vec<sysinfo::Pid> {
+  system
+    .processes()
+    .values()
+    .filter(|process| !is_thread(process))
+    .map(|process| process.pid())
+    .collect()
+}
+
+/// The `n` processes with the most disk I/O since the last refresh, busiest first
+pub(crate) fn top_by_io(system: &sysinfo::System, n: usize) -> Vec<&sysinfo::Process> {
+  let mut processes = system
+    .processes()
+    .values()
+    .filter(|process| !is_thread(process))
+    .collect::<Vec<_>>();
+  processes.sort_unstable_by_key(|process| {
+    let usage = process.disk_usage();
+    std::cmp::Reverse((
+      usage.read_bytes + usage.written_bytes,
+      usage.total_read_bytes + usage.total_written_bytes,
+    ))
+  });
+  processes.truncate(n);
+  processes
+}
+
+/// Map every pid to its children, sorted by pid
+fn children_by_parent(system: &sysinfo::System) -> HashMap<sysinfo::Pid, Vec<&sysinfo::Process>> {
+  let mut children: HashMap<_, Vec<_>> = HashMap::new();
+  for process in system.processes().values() {
+    if is_thread(process) {
+      continue;
+    }
+    if let Some(parent) = process.parent() {
+      children.entry(parent).or_default().push(process);
+    }
+  }
+  for list in children.values_mut() {
+    list.sort_unstable_by_key(|process| process.pid());
+  }
+  children
+}
+
+fn build_tree_node(
+  process: &sysinfo::Process,
+  children: &HashMap<sysinfo::Pid, Vec<&sysinfo::Process>>,
+  visited: &mut HashSet<sysinfo::Pid>,
+) -> ProcessTreeNode {
+  visited.insert(process.pid());
+  ProcessTreeNode {
+    pid: process.pid().as_u32(),
+    name: process.name().to_string_lossy().into_owned(),
+    children: children
+      .get(&process.pid())
+      .into_iter()
+      .flatten()
+      // pids may be reused between two reads, so guard against cycles
+      .filter_map(|child| {
+        if visited.contains(&child.pid()) {
+          None
+        } else {
+          Some(build_tree_node(child, children, visited))
+        }
+      })
+      .collect::<Vec<_>>(),
+  }
+}
+
+/// Build the process tree from parent links.
+///
+/// Without `root`, every process whose parent is unknown becomes a root.
+pub(crate) fn tree(system: &sysinfo::System, root: Option<sysinfo::Pid>) -> Vec<ProcessTreeNode> {
+  let children = children_by_parent(system);
+  let mut visited = HashSet::new();
+  let roots = match root {
+    Some(pid) => system.process(pid).into_iter().collect::<Vec<_>>(),
+    None => {
+      let mut roots = system
+        .processes()
+        .values()
+        .filter(|process| {
+          !is_thread(process)
+            && process
+              .parent()
+              .is_none_or(|parent| system.process(parent).is_none())
+        })
+        .collect::<Vec<_>>();
+      roots.sort_unstable_by_key(|process| process.pid());
+      roots
+    }
+  };
+  roots
+    .into_iter()
+    .map(|process| build_tree_node(process, &children, &mut visited))
+    .collect()
+}
+
+pub(crate) fn find_by_name<'a>(
+  system: &'a sysinfo::System,
+  pattern: &str,
+  options: ProcessesByNameOptions,
+) -> Vec<&'a sysinfo::Process> {
+  let exact = options.exact.unwrap_or(false);
+  if !options.match_cmdline.unwrap_or(false) {
+    let name = OsStr::new(pattern);
+    return if exact {
+      system
+        .processes_by_exact_name(name)
+        .filter(|process| !is_thread(process))
+        .collect()
+    } else {
+      system
+        .processes_by_name(name)
+        .filter(|process| !is_thread(process))
+        .collect()
+    };
+  }
+  let matches = |haystack: &str| {
+    if exact {
+      haystack == pattern
+    } else {
+      haystack.contains(pattern)
+    }
+  };
+  system
+    .processes()
+    .values()
+    .filter(|process| !is_thread(process))
+    .filter(|process| {
+      let cmdline = process
+        .cmd()
+        .iter()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+      matches(&process.name().to_string_lossy()) || matches(&cmdline)
+    })
+    .collect()
+}
+
+#[napi]
+impl Process {
+  #[napi]
+  pub fn pid(&self) -> u32 {
+    self.pid.as_u32()
+  }
+
+  #[napi]
+  /// Parent process id, `null` if the process has no parent
+  pub fn ppid(&self) -> Result<Option<u32>> {
+    self.with_inner(|process| process.parent().map(|pid| pid.as_u32()))
+  }
+
+  #[napi]
+  pub fn name(&self) -> Result<String> {
+    self.with_inner(|process| process.name().to_string_lossy().into_owned())
+  }
+
+  #[napi]
+  /// Path to the executable, `null` if it can't be read
+  pub fn exe(&self) -> Result<Option<String>> {
+    self.with_inner(|process| process.exe().map(|exe| exe.to_string_lossy().into_owned()))
+  }
+
+  #[napi]
+  pub fn cmd(&self) -> Result<Vec<String>> {
+    self.with_inner(|process| {
+      process
+        .cmd()
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect()
+    })
+  }
+
+  #[napi]
+  /// Current working directory, `null` if it can't be read
+  pub fn cwd(&self) -> Result<Option<String>> {
+    self.with_inner(|process| process.cwd().map(|cwd| cwd.to_string_lossy().into_owned()))
+  }
+
+  #[napi]
+  /// Name of the user owning the process, `null` if it's unknown or the owners were not refreshed,
+  /// see `ProcessRefreshKind.user`
+  pub fn user(&self) -> Result<Option<String>> {
+    let uid = self.with_inner(|process| process.user_id().cloned())?;
+    Ok(uid.and_then(|uid| {
+      self
+        .sys
+        .user_list()
+        .get_user_by_id(&uid)
+        .map(|user| user.name().to_string())
+    }))
+  }
+
+  #[napi]
+  pub fn status(&self) -> Result<String> {
+    self.with_inner(|process| process.status().to_string())
+  }
+
+  #[napi]
+  /// Start time of the process in seconds since UNIX epoch
+  pub fn start_time(&self) -> Result<u64> {
+    self.with_inner(|process| process.start_time())
+  }
+
+  #[napi]
+  /// Time the process has been running in seconds
+  pub fn run_time(&self) -> Result<u64> {
+    self.with_inner(|process| process.run_time())
+  }
+
+  #[napi]
+  /// Cpu usage in percent, it can be over 100% on multi-core systems
+  pub fn cpu_usage(&self) -> Result<f32> {
+    self.with_inner(|process| process.cpu_usage())
+  }
+
+  #[napi]
+  /// Resident memory in bytes
+  pub fn memory(&self) -> Result<u64> {
+    self.with_inner(|process| process.memory())
+  }
+
+  #[napi]
+  /// Virtual memory in bytes
+  pub fn virtual_memory(&self) -> Result<u64> {
+    self.with_inner(|process| process.virtual_memory())
+  }
+
+  #[napi]
+  pub fn disk_usage(&self) -> Result<DiskUsage> {
+    self.with_inner(|process| process.disk_usage().into())
+  }
+
+  #[napi]
+  /// All processes below this one in the process tree, in breadth-first order
+  pub fn descendants(&self, env: Env) -> Result<Vec<Process>> {
+    let system = self.sys.system();
+    let children = children_by_parent(&system);
+    let mut visited = HashSet::from([self.pid]);
+    let mut queue = VecDeque::from([self.pid]);
+    let mut descendants = Vec::new();
+    while let Some(pid) = queue.pop_front() {
+      for child in children.get(&pid).into_iter().flatten() {
+        if visited.insert(child.pid()) {
+          queue.push_back(child.pid());
+          descendants.push(Process::new(env, &self.sys, child.pid())?);
+        }
+      }
+    }
+    Ok(descendants)
+  }
+
+  #[napi]
+  /// Parent, grandparent and so on up to the root of the process tree
+  pub fn ancestors(&self, env: Env) -> Result<Vec<Process>> {
+    let system = self.sys.system();
+    let mut visited = HashSet::from([self.pid]);
+    let mut ancestors = Vec::new();
+    let mut current = system.process(self.pid).ok_or_else(|| self.not_found())?;
+    while let Some(parent) = current
+      .parent()
+      .filter(|pid| visited.insert(*pid))
+      .and_then(|pid| system.process(pid))
+    {
+      ancestors.push(Process::new(env, &self.sys, parent.pid())?);
+      current = parent;
+    }
+    Ok(ancestors)
+  }
+
+  #[napi]
+  /// Send `signal` to the process, defaults to `Signal.Kill`
+  pub fn kill(&self, signal: Option<Signal>) -> KillResult {
+    self
+      .with_inner(|process| kill(process, signal))
+      .unwrap_or_else(|_| KillResult::not_found())
+  }
+}
@@ -0,0 +1,205 @@
+use napi_derive::napi;
+use sysinfo::UpdateKind;
+
+#[napi(object)]
+/// Which information to refresh, sections that are not provided are skipped.
+///
+/// ```js
+/// import { SysInfo } from '@napi-rs/sysinfo';
+/// const s = new SysInfo({ memory: { ram: true } });
+///
+/// s.refresh({ cpu: { usage: true }, memory: { ram: true, swap: true } })
+/// ```
+pub struct RefreshKind {
+  pub cpu: Option<CpuRefreshKind>,
+  pub memory: Option<MemoryRefreshKind>,
+  pub processes: Option<ProcessRefreshKind>,
+}
+
+#[napi(object)]
+pub struct CpuRefreshKind {
+  /// Defaults to `false`
+  pub usage: Option<bool>,
+  /// Defaults to `false`
+  pub frequency: Option<bool>,
+}
+
+#[napi(object)]
+pub struct MemoryRefreshKind {
+  /// Defaults to `false`
+  pub ram: Option<bool>,
+  /// Defaults to `false`
+  pub swap: Option<bool>,
+}
+
+#[napi(object)]
+/// The process list, names and parent pids are always refreshed, the rest is opt-in.
+pub struct ProcessRefreshKind {
+  /// Defaults to `false`
+  pub cpu: Option<bool>,
+  /// Defaults to `false`
+  pub memory: Option<bool>,
+  /// Defaults to `false`
+  pub disk_usage: Option<bool>,
+  /// Defaults to `false`
+  pub exe: Option<bool>,
+  /// Defaults to `false`
+  pub cmd: Option<bool>,
+  /// Defaults to `false`
+  pub cwd: Option<bool>,
+  /// Defaults to `false`
+  pub root: Option<bool>,
+  /// Defaults to `false`
+  pub environ: Option<bool>,
+  /// Defaults to `false`
+  pub user: Option<bool>,
+  /// List the threads of the processes on Linux, defaults to `true`
+  pub tasks: Option<bool>,
+}
+
+/// Enabled values are read again on every refresh, a process may change its `cwd` or `environ`
+fn update_kind(enabled: Option<bool>) -> UpdateKind {
+  if enabled.unwrap_or(false) {
+    UpdateKind::Always
+  } else {
+    UpdateKind::Never
+  }
+}
+
+impl From<RefreshKind> for sysinfo::RefreshKind {
+  fn from(value: RefreshKind) -> Self {
+    let mut kind = sysinfo::RefreshKind::nothing();
+    if let Some(cpu) = value.cpu {
+      kind = kind.with_cpu(cpu.into());
+    }
+    if let Some(memory) = value.memory {
+      kind = kind.with_memory(memory.into());
+    }
+    if let Some(processes) = value.processes {
+      kind = kind.with_processes(processes.into());
+    }
+    kind
+  }
+}
+
+impl From<CpuRefreshKind> for sysinfo::CpuRefreshKind {
+  fn from(value: CpuRefreshKind) -> Self {
+    let mut kind = sysinfo::CpuRefreshKind::nothing();
+    if value.usage.unwrap_or(false) {
+      kind = kind.with_cpu_usage();
+    }
+    if value.frequency.unwrap_or(false) {
+      kind = kind.with_frequency();
+    }
+    kind
+  }
+}
+
+impl From<MemoryRefreshKind> for sysinfo::MemoryRefreshKind {
+  fn from(value: MemoryRefreshKind) -> Self {
+    let mut kind = sysinfo::MemoryRefreshKind::nothing();
+    if value.ram.unwrap_or(false) {
+      kind = kind.with_ram();
+    }
+    if value.swap.unwrap_or(false) {
+      kind = kind.with_swap();
+    }
+    kind
+  }
+}
+
+impl From<ProcessRefreshKind> for sysinfo::ProcessRefreshKind {
+  fn from(value: ProcessRefreshKind) -> Self {
+    let mut kind = sysinfo::ProcessRefreshKind::nothing()
+      .with_exe(update_kind(value.exe))
+      .with_cmd(update_kind(value.cmd))
+      .with_cwd(update_kind(value.cwd))
+      .with_root(update_kind(value.root))
+      .with_environ(update_kind(value.environ))
+      .with_user(update_kind(value.user));
+    if value.cpu.unwrap_or(false) {
+      kind = kind.with_cpu();
+    }
+    if value.memory.unwrap_or(false) {
+      kind = kind.with_memory();
+    }
+    if value.disk_usage.unwrap_or(false) {
+      kind = kind.with_disk_usage();
+    }
+    if !value.tasks.unwrap_or(true) {
+      kind = kind.without_tasks();
+    }
+    kind
+  }
+}
+
+/// The update kind refreshing the most of `a` and `b`
+fn either_update_kind(a: UpdateKind, b: UpdateKind) -> UpdateKind {
+  match (a, b) {
+    (UpdateKind::Always, _) | (_, UpdateKind::Always) => UpdateKind::Always,
+    (UpdateKind::OnlyIfNotSet, _) | (_, UpdateKind::OnlyIfNotSet) => UpdateKind::OnlyIfNotSet,
+    _ => UpdateKind::Never,
+  }
+}
+
+fn either<T>(a: Option<T>, b: Option<T>, merge: impl FnOnce(T, T) -> T) -> Option<T> {
+  match (a, b) {
+    (Some(a), Some(b)) => Some(merge(a, b)),
+    (a, b) => a.or(b),
+  }
+}
+
+/// Everything `a` or `b` refreshes
+pub(crate) fn union(a: sysinfo::RefreshKind, b: sysinfo::RefreshKind) -> sysinfo::RefreshKind {
+  let mut kind = sysinfo::RefreshKind::nothing();
+  if let Some(cpu) = either(a.cpu(), b.cpu(), |a, b| {
+    let mut cpu = sysinfo::CpuRefreshKind::nothing();
+    if a.cpu_usage() || b.cpu_usage() {
+      cpu = cpu.with_cpu_usage();
+    }
+    if a.frequency() || b.frequency() {
+      cpu = cpu.with_frequency();
+    }
+    cpu
+  }) {
+    kind = kind.with_cpu(cpu);
+  }
+  if let Some(memory) = either(a.memory(), b.memory(), |a, b| {
+    let mut memory = sysinfo::MemoryRefreshKind::nothing();
+    if a.ram() || b.ram() {
+      memory = memory.with_ram();
+    }
+    if a.swap() || b.swap() {
+      memory = memory.with_swap();
+    }
+    memory
+  }) {
+    kind = kind.with_memory(memory);
+  }
+  if let Some(processes) = either(a.processes(), b.processes(), |a, b| {
+    let mut processes = sysinfo::ProcessRefreshKind::nothing()
+      .with_exe(either_update_kind(a.exe(), b.exe()))
+      .with_cmd(either_update_kind(a.cmd(), b.cmd()))
+      .with_cwd(either_update_kind(a.cwd(), b.cwd()))
+      .with_root(either_update_kind(a.root(), b.root()))
+      .with_environ(either_update_kind(a.environ(), b.environ()))
+      .with_user(either_update_kind(a.user(), b.user()));
+    if a.cpu() || b.cpu() {
+      processes = processes.with_cpu();
+    }
+    if a.memory() || b.memory() {
+      processes = processes.with_memory();
+    }
+    if a.disk_usage() || b.disk_usage() {
+      processes = processes.with_disk_usage();
+    }
+    processes = match a.tasks() || b.tasks() {
+      true => processes.with_tasks(),
+      false => processes.without_tasks(),
+    };
+    processes
+  }) {
+    kind = kind.with_processes(processes);
+  }
+  kind
+}
@@ -0,0 +1,65 @@
+//! Readers of the pseudo files Linux exposes under `/proc` and `/sys`.
+//!
+//! Readers of these files take a `root` the absolute paths are joined to. It's `/` outside of
+//! tests, which point it to a directory of `__test__/fixtures` instead.
+
+use std::fs;
+use std::path::Path;
+
+pub(crate) fn read_trimmed(path: &Path) -> Option<String> {
+  fs::read_to_string(path)
+    .ok()
+    .map(|content| content.trim().to_string())
+}
+
+/// Entries of `dir` sorted by name, missing directories are empty
+pub(crate) fn sorted_entries(dir: &Path) -> Vec<fs::DirEntry> {
+  let mut entries = fs::read_dir(dir)
+    .map(|entries| entries.flatten().collect::<Vec<_>>())
+    .unwrap_or_default();
+  entries.sort_by_key(|entry| entry.file_name());
+  entries
+}
+
+/// Parse a cpu list like `0-3,8,10-11`, as used by `shared_cpu_list` or `cpuset.cpus`
+pub(crate) fn parse_cpu_list(list: &str) -> Vec<u32> {
+  list
+    .trim()
+    .split(',')
+    .filter(|range| !range.is_empty())
+    .filter_map(|range| match range.split_once('-') {
+      Some((start, end)) => Some(start.trim().parse().ok()?..=end.trim().parse().ok()?),
+      None => {
+        let cpu = range.trim().parse().ok()?;
+        Some(cpu..=cpu)
+      }
+    })
+    .flatten()
+    .collect()
+}
+
+/// The `cpuN` directories under `<root>/sys/devices/system/cpu`, sorted by N
+pub(crate) fn cpu_dirs(root: &Path) -> Vec<(u32, std::path::PathBuf)> {
+  let mut cpus = sorted_entries(&root.join("sys/devices/system/cpu"))
+    .into_iter()
+    .filter_map(|entry| {
+      let id = entry
+        .file_name()
+        .to_str()?
+        .strip_prefix("cpu")?
+        .parse()
+        .ok()?;
+      Some((id, entry.path()))
+    })
+    .collect::<Vec<_>>();
+  cpus.sort_by_key(|(id, _)| *id);
+  cpus
+}
+
+/// Path of a file or directory under `__test__/fixtures`
+#[cfg(test)]
+pub(crate) fn fixture(path: &str) -> std::path::PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("__test__/fixtures")
+    .join(path)
+}
@@ -0,0 +1,151 @@
+use std::sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard};
+
+use napi::{Env, Result, Task};
+
+use crate::{refresh, SysInfo};
+
+/// The `System` of a `SysInfo` and a second one refreshed off the main thread.
+///
+/// The threadpool refreshes `back`, swaps it with `front` under a brief write lock, then refreshes
+/// the new `back` the same way, so getters on the main thread never wait for a refresh. Refreshing
+/// both keeps their cpu usage deltas over the same intervals, and neither lacks what the other
+/// gathered.
+pub struct SharedSystem {
+  front: RwLock<sysinfo::System>,
+  back: Mutex<sysinfo::System>,
+  /// What `front` was refreshed with since `back` last was
+  stale: Mutex<sysinfo::RefreshKind>,
+}
+
+impl SharedSystem {
+  pub(crate) fn new(kinds: sysinfo::RefreshKind) -> Self {
+    Self {
+      front: RwLock::new(sysinfo::System::new_with_specifics(kinds)),
+      back: Mutex::new(sysinfo::System::new_with_specifics(kinds)),
+      stale: Mutex::new(sysinfo::RefreshKind::nothing()),
+    }
+  }
+
+  pub(crate) fn read(&self) -> RwLockReadGuard<'_, sysinfo::System> {
+    self.front.read().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  fn mark_stale(&self, kinds: sysinfo::RefreshKind) {
+    let mut stale = self.stale.lock().unwrap_or_else(PoisonError::into_inner);
+    *stale = refresh::union(*stale, kinds);
+  }
+
+  /// Refresh the `System` getters read on the calling thread
+  pub(crate) fn refresh(&self, kinds: sysinfo::RefreshKind) {
+    self
+      .front
+      .write()
+      .unwrap_or_else(PoisonError::into_inner)
+      .refresh_specifics(kinds);
+    self.mark_stale(kinds);
+  }
+
+  /// Refresh the other `System` with `kinds`, along with what it missed, then with `then` and
+  /// swap it in. The previous front is refreshed with `kinds` once swapped out.
+  ///
+  /// Meant for the threadpool, concurrent calls run one after the other.
+  pub(crate) fn refresh_and_swap<T>(
+    &self,
+    kinds: sysinfo::RefreshKind,
+    then: impl FnOnce(&mut sysinfo::System) -> T,
+  ) -> T {
+    let mut back = self.back.lock().unwrap_or_else(PoisonError::into_inner);
+    let stale = std::mem::replace(
+      &mut *self.stale.lock().unwrap_or_else(PoisonError::into_inner),
+      sysinfo::RefreshKind::nothing(),
+    );
+    back.refresh_specifics(refresh::union(stale, kinds));
+    let output = then(&mut back);
+    std::mem::swap(
+      &mut *self.front.write().unwrap_or_else(PoisonError::into_inner),
+      &mut *back,
+    );
+    back.refresh_specifics(kinds);
+    output
+  }
+}
+
+pub struct CreateSysInfo {
+  kinds: sysinfo::RefreshKind,
+}
+
+impl CreateSysInfo {
+  pub(crate) fn new(kinds: Option<sysinfo::RefreshKind>) -> Self {
+    Self {
+      kinds: kinds.unwrap_or_else(sysinfo::RefreshKind::everything),
+    }
+  }
+}
+
+impl Task for CreateSysInfo {
+  type Output = SharedSystem;
+  type JsValue = SysInfo;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    Ok(SharedSystem::new(self.kinds))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(SysInfo::with_system(output))
+  }
+}
+
+/// Refresh the `System` of a `SysInfo` in the libuv threadpool, see `SharedSystem`
+pub struct Refresh {
+  system: Arc<SharedSystem>,
+  kinds: sysinfo::RefreshKind,
+}
+
+impl Refresh {
+  pub(crate) fn new(system: Arc<SharedSystem>, kinds: Option<sysinfo::RefreshKind>) -> Self {
+    Self {
+      system,
+      kinds: kinds.unwrap_or_else(sysinfo::RefreshKind::everything),
+    }
+  }
+}
+
+impl Task for Refresh {
+  type Output = ();
+  type JsValue = ();
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    self.system.refresh_and_swap(self.kinds, |_| ());
+    Ok(())
+  }
+
+  fn resolve(&mut self, _env: Env, _output: Self::Output) -> Result<Self::JsValue> {
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn swapped_in_system_catches_up() {
+    let system = SharedSystem::new(sysinfo::RefreshKind::nothing());
+    system.refresh(
+      sysinfo::RefreshKind::nothing().with_memory(sysinfo::MemoryRefreshKind::everything()),
+    );
+    assert!(system.read().total_memory() > 0);
+    // The back was never refreshed with memory
+    let cpus = system.refresh_and_swap(
+      sysinfo::RefreshKind::nothing().with_cpu(sysinfo::CpuRefreshKind::everything()),
+      |system| system.cpus().len(),
+    );
+    assert!(cpus > 0);
+    assert!(system.read().total_memory() > 0);
+    assert_eq!(system.read().cpus().len(), cpus);
+    // Refreshed once swapped out
+    system.refresh_and_swap(sysinfo::RefreshKind::nothing(), |system| {
+      assert_eq!(system.cpus().len(), cpus);
+    });
+  }
+}
@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use napi_derive::napi;
+
+use crate::sysfs::{cpu_dirs, parse_cpu_list, read_trimmed};
+
+#[napi(object, object_from_js = false)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct CpuPackage {
+  pub id: u32,
+  pub cores: Vec<CpuCore>,
+}
+
+#[napi(object, object_from_js = false)]
+#[derive(Debug, PartialEq, Eq)]
+/// A physical core, `cpus` are the logical cpu numbers of its SMT siblings.
+pub struct CpuCore {
+  /// Index of the core in its package, by die then by the id the hardware gives it. Hardware ids
+  /// have holes on hybrid cpus and repeat across dies and arm64 clusters.
+  pub id: u32,
+  pub cpus: Vec<u32>,
+}
+
+/// Logical cpus grouped by package, then by the key of their core. Cores are numbered in key
+/// order.
+fn group(cpus: impl IntoIterator<Item = (u32, (u32, u32, u32), u32)>) -> Vec<CpuPackage> {
+  let mut packages = BTreeMap::<u32, BTreeMap<(u32, u32, u32), Vec<u32>>>::new();
+  for (package, core, cpu) in cpus {
+    packages
+      .entry(package)
+      .or_default()
+      .entry(core)
+      .or_default()
+      .push(cpu);
+  }
+  packages
+    .into_iter()
+    .map(|(id, cores)| CpuPackage {
+      id,
+      cores: cores
+        .into_iter()
+        .enumerate()
+        .map(|(id, (_, cpus))| CpuCore {
+          id: id as u32,
+          cpus,
+        })
+        .collect(),
+    })
+    .collect()
+}
+
+/// Topology from `<root>/sys/devices/system/cpu/cpu*/topology`, empty if it can't be read
+pub(crate) fn read_sysfs(root: &Path) -> Vec<CpuPackage> {
+  group(cpu_dirs(root).into_iter().filter_map(|(cpu, dir)| {
+    let topology = dir.join("topology");
+    let read = |name: &str| read_trimmed(&topology.join(name))?.parse::<i64>().ok();
+    // Offline cpus don't have a topology, `-1` is written when the firmware doesn't tell
+    let package = read("physical_package_id")?.max(0) as u32;
+    let core = read("core_id")?;
+    let core = if core < 0 { cpu } else { core as u32 };
+    // `core_id` is only unique within a die, and within a cluster on some arm64 systems. The
+    // first SMT sibling tells the cores apart.
+    let die = read("die_id").unwrap_or(0).max(0) as u32;
+    let first_sibling = read_trimmed(&topology.join("thread_siblings_list"))
+      .and_then(|list| parse_cpu_list(&list).into_iter().min())
+      .unwrap_or(cpu);
+    Some((package, (die, core, first_sibling), cpu))
+  }))
+}
+
+/// Split the x2APIC ID of every logical cpu into package, core and SMT fields, the shifts come
+/// from CPUID leaf 0x1F or 0xB.
+///
+/// The IDs of hybrid parts have holes, an efficiency core without SMT takes a whole SMT field.
+#[cfg(any(all(windows, any(target_arch = "x86", target_arch = "x86_64")), test))]
+fn from_apic_ids(
+  apic_ids: impl IntoIterator<Item = (u32, u32)>,
+  smt_shift: u32,
+  package_shift: u32,
+) -> Vec<CpuPackage> {
+  let package_mask = u32::MAX.checked_shl(package_shift).unwrap_or(0);
+  group(apic_ids.into_iter().map(|(cpu, apic_id)| {
+    let package = apic_id.checked_shr(package_shift).unwrap_or(0);
+    let core = (apic_id & !package_mask)
+      .checked_shr(smt_shift)
+      .unwrap_or(0);
+    (package, (0, core, 0), cpu)
+  }))
+}
+
+/// A topology where the SMT siblings of a core have consecutive indexes and every core has the
+/// same number of them
+fn uniform(logical: u32, per_package: u32, per_core: u32) -> Vec<CpuPackage> {
+  let per_package = per_package.clamp(1, logical.max(1));
+  let per_core = per_core.clamp(1, per_package);
+  (0..logical.div_ceil(per_package))
+    .map(|package| {
+      let first = package * per_package;
+      let last = (first + per_package).min(logical);
+      CpuPackage {
+        id: package,
+        cores: (first..last)
+          .step_by(per_core as usize)
+          .enumerate()
+          .map(|(id, first)| CpuCore {
+            id: id as u32,
+            cpus: (first..(first + per_core).min(last)).collect(),
+          })
+          .collect(),
+      }
+    })
+    .collect()
+}
+
+/// The levels of CPUID leaf 0x1F, or 0xB on older cpus
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn topology_levels() -> Option<Vec<raw_cpuid::ExtendedTopologyLevel>> {
+  let cpuid = raw_cpuid::CpuId::new();
+  let levels = cpuid
+    .get_extended_topology_info_v2()
+    .or_else(|| cpuid.get_extended_topology_info())?
+    .collect::<Vec<_>>();
+  (!levels.is_empty()).then_some(levels)
+}
+
+/// Topology from the x2APIC ID of every cpu, read by moving a thread from one cpu to another
+#[cfg(all(windows, any(target_arch = "x86", target_arch = "x86_64")))]
+fn read_cpuid() -> Option<Vec<CpuPackage>> {
+  let levels = topology_levels()?;
+  let smt_shift = levels
+    .iter()
+    .find(|level| level.level_type() == raw_cpuid::TopologyType::SMT)
+    .map_or(0, |level| level.shift_right_for_next_apic_id());
+  // The last level spans the whole package
+  let package_shift = levels.last()?.shift_right_for_next_apic_id();
+  let apic_ids = crate::core_type::on_every_cpu(|| raw_cpuid::cpuid!(0xB, 0).edx);
+  (!apic_ids.is_empty()).then(|| from_apic_ids(apic_ids, smt_shift, package_shift))
+}
+
+/// Logical cpus per core and per package, Intel Macs are never hybrid so every core has the same
+/// number of SMT siblings
+#[cfg(all(not(windows), any(target_arch = "x86", target_arch = "x86_64")))]
+fn read_cpuid() -> Option<(u32, u32)> {
+  let levels = topology_levels()?;
+  let per_core = levels
+    .iter()
+    .find(|level| level.level_type() == raw_cpuid::TopologyType::SMT)
+    .map_or(1, |level| level.processors() as u32);
+  let per_package = levels.last()?.processors() as u32;
+  Some((per_core, per_package))
+}
+
+/// `logical` is the number of logical cpus, used when the topology has to be inferred
+pub(crate) fn detect(logical: u32) -> Vec<CpuPackage> {
+  let packages = read_sysfs(Path::new("/"));
+  if !packages.is_empty() {
+    return packages;
+  }
+  #[cfg(all(windows, any(target_arch = "x86", target_arch = "x86_64")))]
+  if let Some(packages) = read_cpuid() {
+    return packages;
+  }
+  #[cfg(all(not(windows), any(target_arch = "x86", target_arch = "x86_64")))]
+  if let Some((per_core, per_package)) = read_cpuid() {
+    return uniform(logical, per_package, per_core);
+  }
+  let physical = sysinfo::System::physical_core_count().unwrap_or(logical as usize) as u32;
+  uniform(logical, logical, logical / physical.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sysfs::fixture;
+
+  fn core(id: u32, cpus: &[u32]) -> CpuCore {
+    CpuCore {
+      id,
+      cpus: cpus.to_vec(),
+    }
+  }
+
+  #[test]
+  fn hybrid_fixture() {
+    let root = fixture("sysfs");
+    assert_eq!(
+      read_sysfs(&root),
+      [CpuPackage {
+        id: 0,
+        cores: vec![
+          core(0, &[0, 1]),
+          core(1, &[2, 3]),
+          core(2, &[4]),
+          core(3, &[5]),
+          core(4, &[6]),
+          core(5, &[7]),
+        ],
+      }]
+    );
+  }
+
+  #[test]
+  fn multi_die_fixture() {
+    let root = fixture("topology-multi-die");
+    assert_eq!(
+      read_sysfs(&root),
+      [CpuPackage {
+        id: 0,
+        cores: vec![core(0, &[0]), core(1, &[1]), core(2, &[2]), core(3, &[3])],
+      }]
+    );
+  }
+
+  #[test]
+  fn hybrid_apic_ids() {
+    // The x2APIC IDs of the sysfs fixture, 2 performance cores with SMT then 4 efficiency cores
+    let apic_ids = [0, 1, 8, 9, 16, 18, 20, 22];
+    let root = fixture("sysfs");
+    assert_eq!(from_apic_ids((0..).zip(apic_ids), 1, 6), read_sysfs(&root));
+    assert_eq!(
+      from_apic_ids((0..).zip([0, 1, 64, 65]), 1, 6),
+      [
+        CpuPackage {
+          id: 0,
+          cores: vec![core(0, &[0, 1])],
+        },
+        CpuPackage {
+          id: 1,
+          cores: vec![core(0, &[2, 3])],
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn uniform_topology() {
+    assert_eq!(
+      uniform(8, 4, 2),
+      [
+        CpuPackage {
+          id: 0,
+          cores: vec![core(0, &[0, 1]), core(1, &[2, 3])],
+        },
+        CpuPackage {
+          id: 1,
+          cores: vec![core(0, &[4, 5]), core(1, &[6, 7])],
+        },
+      ]
+    );
+    assert_eq!(
+      uniform(3, 3, 1),
+      [CpuPackage {
+        id: 0,
+        cores: vec![core(0, &[0]), core(1, &[1]), core(2, &[2])],
+      }]
+    );
+  }
+}
@@ -0,0 +1,38 @@
+use napi_derive::napi;
+
+#[napi(object, object_from_js = false)]
+pub struct Group {
+  pub gid: u32,
+  pub name: String,
+}
+
+#[napi(object, object_from_js = false)]
+pub struct User {
+  /// Numeric id on unix, SID on Windows
+  pub uid: String,
+  /// Primary group id
+  pub gid: u32,
+  pub name: String,
+  /// Groups the user is a member of
+  pub groups: Vec<Group>,
+}
+
+impl From<&sysinfo::Group> for Group {
+  fn from(value: &sysinfo::Group) -> Self {
+    Self {
+      gid: **value.id(),
+      name: value.name().to_string(),
+    }
+  }
+}
+
+impl From<&sysinfo::User> for User {
+  fn from(value: &sysinfo::User) -> Self {
+    Self {
+      uid: (**value.id()).to_string(),
+      gid: *value.group_id(),
+      name: value.name().to_string(),
+      groups: value.groups().iter().map(Group::from).collect(),
+    }
+  }
+}
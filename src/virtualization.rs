@@ -0,0 +1,183 @@
+use std::path::Path;
+
+use napi_derive::napi;
+
+use crate::sysfs::read_trimmed;
+
+#[napi(object)]
+#[derive(Debug, PartialEq, Eq)]
+/// Names follow `systemd-detect-virt`.
+pub struct Virtualization {
+  /// `kvm`, `qemu`, `vmware`, `microsoft`, `xen`, `oracle`, `amazon`, `google`..., unset on bare
+  /// metal
+  pub hypervisor: Option<String>,
+  /// `docker`, `podman`, `kubernetes`, `lxc` or `containerd`, unset outside of containers
+  pub container: Option<String>,
+  /// Running in the Windows Subsystem for Linux
+  pub is_wsl: bool,
+}
+
+/// Leaf 0x40000000 holds the vendor signature once the hypervisor bit of leaf 1 is set, `None` on
+/// bare metal
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn cpuid_hypervisor() -> Option<String> {
+  use raw_cpuid::Hypervisor;
+
+  let info = raw_cpuid::CpuId::new().get_hypervisor_info()?;
+  let name = match info.identify() {
+    Hypervisor::Xen => "xen".to_string(),
+    Hypervisor::VMware => "vmware".to_string(),
+    Hypervisor::HyperV => "microsoft".to_string(),
+    Hypervisor::KVM => "kvm".to_string(),
+    Hypervisor::QEMU => "qemu".to_string(),
+    Hypervisor::Bhyve => "bhyve".to_string(),
+    Hypervisor::QNX => "qnx".to_string(),
+    Hypervisor::ACRN => "acrn".to_string(),
+    Hypervisor::Unknown(ebx, ecx, edx) => match [ebx, ecx, edx].map(u32::to_le_bytes).concat() {
+      signature if signature == b"VBoxVBoxVBox" => "oracle".to_string(),
+      // Older Parallels releases swap the bytes of every register
+      signature if signature == b"prl hyperv  " || signature == b" lrpepyh  vr" => {
+        "parallels".to_string()
+      }
+      _ => "unknown".to_string(),
+    },
+  };
+  Some(name)
+}
+
+/// The hypervisor named by the DMI vendor and product, Linux only
+fn dmi_hypervisor(root: &Path) -> Option<&'static str> {
+  let dmi = root.join("sys/class/dmi/id");
+  ["sys_vendor", "product_name", "board_vendor", "bios_vendor"]
+    .into_iter()
+    .filter_map(|file| read_trimmed(&dmi.join(file)))
+    .find_map(|value| {
+      [
+        ("KVM", "kvm"),
+        ("OpenStack", "kvm"),
+        ("QEMU", "qemu"),
+        ("VMware", "vmware"),
+        ("VMW", "vmware"),
+        ("innotek GmbH", "oracle"),
+        ("VirtualBox", "oracle"),
+        ("Oracle Corporation", "oracle"),
+        ("Xen", "xen"),
+        ("Bochs", "bochs"),
+        ("Parallels", "parallels"),
+        ("BHYVE", "bhyve"),
+        ("Amazon EC2", "amazon"),
+        ("Google Compute Engine", "google"),
+        ("Virtual Machine", "microsoft"),
+      ]
+      .into_iter()
+      .find(|(prefix, _)| value.starts_with(prefix))
+      .map(|(_, name)| name)
+    })
+}
+
+/// CPUID decides whether we're virtualized, bare metal cloud instances have the same DMI strings as
+/// their VMs. DMI only refines the name, `amazon` rather than `kvm`.
+fn hypervisor(cpuid: Option<String>, dmi: Option<&str>) -> Option<String> {
+  let cpuid = cpuid?;
+  Some(dmi.map(str::to_string).unwrap_or(cpuid))
+}
+
+fn container(root: &Path) -> Option<String> {
+  // The pid 1 cgroup only names the engine without a cgroup namespace
+  let from_cgroup = read_trimmed(&root.join("proc/1/cgroup")).and_then(|cgroup| {
+    [
+      ("kubepods", "kubernetes"),
+      ("docker", "docker"),
+      ("libpod", "podman"),
+      ("lxc", "lxc"),
+      ("containerd", "containerd"),
+    ]
+    .into_iter()
+    .find(|(pattern, _)| cgroup.contains(pattern))
+    .map(|(_, name)| name)
+  });
+  let name = from_cgroup
+    .or_else(|| root.join(".dockerenv").exists().then_some("docker"))
+    .or_else(|| root.join("run/.containerenv").exists().then_some("podman"))?;
+  Some(name.to_string())
+}
+
+fn is_wsl(root: &Path) -> bool {
+  read_trimmed(&root.join("proc/sys/kernel/osrelease")).is_some_and(|release| {
+    let release = release.to_lowercase();
+    release.contains("microsoft") || release.contains("wsl")
+  })
+}
+
+/// `cpuid` is what CPUID reports on x86, `None` elsewhere
+fn read(root: &Path, cpuid: Option<Option<String>>) -> Virtualization {
+  Virtualization {
+    hypervisor: match cpuid {
+      Some(cpuid) => hypervisor(cpuid, dmi_hypervisor(root)),
+      // Without CPUID only DMI tells
+      None => dmi_hypervisor(root).map(str::to_string),
+    },
+    container: container(root),
+    is_wsl: is_wsl(root),
+  }
+}
+
+pub(crate) fn detect(root: &Path) -> Virtualization {
+  #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+  let cpuid = Some(cpuid_hypervisor());
+  #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+  let cpuid = None;
+  read(root, cpuid)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sysfs::fixture;
+
+  #[test]
+  fn kubernetes_on_ec2() {
+    let root = fixture("virtualization/kubernetes-ec2");
+    assert_eq!(container(&root).as_deref(), Some("kubernetes"));
+    assert_eq!(dmi_hypervisor(&root), Some("amazon"));
+    assert!(!is_wsl(&root));
+    assert_eq!(
+      hypervisor(Some("kvm".to_string()), Some("amazon")).as_deref(),
+      Some("amazon")
+    );
+    // A metal instance
+    assert_eq!(hypervisor(None, Some("amazon")), None);
+  }
+
+  #[test]
+  fn without_cpuid() {
+    assert_eq!(
+      read(&fixture("virtualization/kubernetes-ec2"), None),
+      Virtualization {
+        hypervisor: Some("amazon".to_string()),
+        container: Some("kubernetes".to_string()),
+        is_wsl: false,
+      }
+    );
+    assert_eq!(
+      read(&fixture("virtualization/podman-wsl"), None),
+      Virtualization {
+        hypervisor: None,
+        container: Some("podman".to_string()),
+        is_wsl: true,
+      }
+    );
+  }
+
+  #[test]
+  fn podman_in_wsl() {
+    let root = fixture("virtualization/podman-wsl");
+    assert_eq!(container(&root).as_deref(), Some("podman"));
+    assert_eq!(dmi_hypervisor(&root), None);
+    assert!(is_wsl(&root));
+    assert_eq!(
+      hypervisor(Some("microsoft".to_string()), None).as_deref(),
+      Some("microsoft")
+    );
+  }
+}
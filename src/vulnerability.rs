@@ -0,0 +1,262 @@
+use std::path::Path;
+
+use napi_derive::napi;
+
+use crate::sysfs::{read_trimmed, sorted_entries};
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VulnerabilityStatus {
+  #[napi(value = "vulnerable")]
+  Vulnerable,
+  #[napi(value = "mitigated")]
+  Mitigated,
+  #[napi(value = "not affected")]
+  NotAffected,
+}
+
+#[napi(object)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct CpuVulnerability {
+  /// File name under `/sys/devices/system/cpu/vulnerabilities`, like `spectre_v2` or `mds`
+  pub name: String,
+  pub status: VulnerabilityStatus,
+  /// What the kernel reports after the status, like `PTI`, or the cpu features a mitigation could
+  /// use when read from CPUID
+  pub detail: Option<String>,
+}
+
+/// Parse a line like `Mitigation: PTI`, `Vulnerable` or `KVM: Mitigation: VMX disabled`.
+///
+/// `Unknown: ...` and anything else the kernel may print count as vulnerable.
+fn parse_status(line: &str) -> (VulnerabilityStatus, Option<String>) {
+  if line.starts_with("Not affected") {
+    return (VulnerabilityStatus::NotAffected, None);
+  }
+  // `itlb_multihit` prefixes the status with the hypervisor it applies to
+  let (hypervisor, status) = match line.strip_prefix("KVM: ") {
+    Some(status) => (Some("KVM"), status),
+    None => (None, line),
+  };
+  let detail = |rest: &str| {
+    let rest = rest.trim_start_matches(':').trim();
+    match (hypervisor, rest.is_empty()) {
+      (_, true) => hypervisor.map(str::to_string),
+      (Some(hypervisor), false) => Some(format!("{hypervisor}: {rest}")),
+      (None, false) => Some(rest.to_string()),
+    }
+  };
+  if let Some(mitigation) = status.strip_prefix("Mitigation") {
+    return (VulnerabilityStatus::Mitigated, detail(mitigation));
+  }
+  match status.strip_prefix("Vulnerable") {
+    Some(rest) => (VulnerabilityStatus::Vulnerable, detail(rest)),
+    None => (VulnerabilityStatus::Vulnerable, Some(line.to_string())),
+  }
+}
+
+/// Empty before Linux 4.15 and outside of Linux
+pub(crate) fn read_sysfs(root: &Path) -> Vec<CpuVulnerability> {
+  sorted_entries(&root.join("sys/devices/system/cpu/vulnerabilities"))
+    .into_iter()
+    .filter_map(|entry| {
+      let (status, detail) = parse_status(&read_trimmed(&entry.path())?);
+      Some(CpuVulnerability {
+        name: entry.file_name().to_str()?.to_string(),
+        status,
+        detail,
+      })
+    })
+    .collect()
+}
+
+/// Speculation control bits of CPUID leaf 7 and, on AMD, of leaf 0x80000008
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", test))]
+#[derive(Debug, Default)]
+struct SpeculationControl {
+  amd: bool,
+  md_clear: bool,
+  ibrs: bool,
+  stibp: bool,
+  ssbd: bool,
+  /// `IA32_ARCH_CAPABILITIES` exists, the `*_NO` bits it holds can't be read from user space
+  arch_capabilities: bool,
+  /// AMD cpus not affected by speculative store bypass
+  ssb_no: bool,
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", test))]
+impl SpeculationControl {
+  /// CPUID only tells which mitigations the cpu supports, not whether the OS enabled them, so
+  /// affected cpus are vulnerable and the supported features go in `detail`
+  fn vulnerabilities(&self) -> Vec<CpuVulnerability> {
+    let entry = |name: &str, status, features: &[(bool, &str)]| {
+      let features = features
+        .iter()
+        .filter(|(supported, _)| *supported)
+        .map(|(_, feature)| *feature)
+        .collect::<Vec<_>>();
+      CpuVulnerability {
+        name: name.to_string(),
+        status,
+        detail: (!features.is_empty()).then(|| features.join(", ")),
+      }
+    };
+    let affected_unless = |not_affected| match not_affected {
+      true => VulnerabilityStatus::NotAffected,
+      false => VulnerabilityStatus::Vulnerable,
+    };
+    vec![
+      entry(
+        "mds",
+        affected_unless(self.amd),
+        &[
+          (self.md_clear, "MD_CLEAR"),
+          (self.arch_capabilities, "ARCH_CAPABILITIES"),
+        ],
+      ),
+      entry(
+        "spec_store_bypass",
+        affected_unless(self.ssb_no),
+        &[(self.ssbd, "SSBD")],
+      ),
+      entry(
+        "spectre_v2",
+        VulnerabilityStatus::Vulnerable,
+        &[
+          (self.ibrs, "IBRS"),
+          (self.stibp, "STIBP"),
+          (self.arch_capabilities, "ARCH_CAPABILITIES"),
+        ],
+      ),
+    ]
+  }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn read_cpuid() -> Vec<CpuVulnerability> {
+  let cpuid = raw_cpuid::CpuId::new();
+  let amd = cpuid
+    .get_vendor_info()
+    .is_some_and(|vendor| matches!(vendor.as_str(), "AuthenticAMD" | "HygonGenuine"));
+  let leaf7 = match raw_cpuid::cpuid!(0, 0).eax >= 7 {
+    true => raw_cpuid::cpuid!(7, 0).edx,
+    false => 0,
+  };
+  let amd_leaf = match raw_cpuid::cpuid!(0x8000_0000, 0).eax >= 0x8000_0008 {
+    true => raw_cpuid::cpuid!(0x8000_0008, 0).ebx,
+    false => 0,
+  };
+  let bit = |register: u32, bit: u32| register & (1 << bit) != 0;
+  SpeculationControl {
+    amd,
+    md_clear: bit(leaf7, 10),
+    ibrs: bit(leaf7, 26) || bit(amd_leaf, 14),
+    stibp: bit(leaf7, 27) || bit(amd_leaf, 15),
+    ssbd: bit(leaf7, 31) || bit(amd_leaf, 24) || bit(amd_leaf, 25),
+    arch_capabilities: bit(leaf7, 29),
+    ssb_no: bit(amd_leaf, 26),
+  }
+  .vulnerabilities()
+}
+
+/// The kernel report on Linux, the mitigations CPUID advertises on other x86 systems
+pub(crate) fn detect() -> Vec<CpuVulnerability> {
+  let vulnerabilities = read_sysfs(Path::new("/"));
+  #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+  if vulnerabilities.is_empty() {
+    return read_cpuid();
+  }
+  vulnerabilities
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sysfs::fixture;
+
+  fn vulnerability(name: &str, status: VulnerabilityStatus, detail: &str) -> CpuVulnerability {
+    CpuVulnerability {
+      name: name.to_string(),
+      status,
+      detail: Some(detail.to_string()).filter(|detail| !detail.is_empty()),
+    }
+  }
+
+  #[test]
+  fn sysfs_fixture() {
+    use VulnerabilityStatus::*;
+
+    let root = fixture("sysfs");
+    assert_eq!(
+      read_sysfs(&root),
+      vec![
+        vulnerability("gather_data_sampling", NotAffected, ""),
+        vulnerability("itlb_multihit", Mitigated, "KVM: VMX disabled"),
+        vulnerability(
+          "mds",
+          Vulnerable,
+          "Clear CPU buffers attempted, no microcode; SMT vulnerable"
+        ),
+        vulnerability("meltdown", Mitigated, "PTI"),
+        vulnerability("mmio_stale_data", Vulnerable, "Unknown: No mitigations"),
+        vulnerability("retbleed", Vulnerable, ""),
+        vulnerability(
+          "spec_store_bypass",
+          Mitigated,
+          "Speculative Store Bypass disabled via prctl"
+        ),
+        vulnerability(
+          "spectre_v1",
+          Mitigated,
+          "usercopy/swapgs barriers and __user pointer sanitization"
+        ),
+        vulnerability(
+          "spectre_v2",
+          Mitigated,
+          "Enhanced / Automatic IBRS; IBPB: conditional; PBRSB-eIBRS: SW sequence; BHI: Vulnerable"
+        ),
+      ]
+    );
+    assert!(read_sysfs(Path::new("/nonexistent")).is_empty());
+    assert_eq!(
+      parse_status("KVM: Vulnerable"),
+      (Vulnerable, Some("KVM".to_string()))
+    );
+  }
+
+  #[test]
+  fn cpuid() {
+    use VulnerabilityStatus::*;
+
+    let intel = SpeculationControl {
+      md_clear: true,
+      ibrs: true,
+      stibp: true,
+      ssbd: true,
+      arch_capabilities: true,
+      ..Default::default()
+    };
+    assert_eq!(
+      intel.vulnerabilities(),
+      vec![
+        vulnerability("mds", Vulnerable, "MD_CLEAR, ARCH_CAPABILITIES"),
+        vulnerability("spec_store_bypass", Vulnerable, "SSBD"),
+        vulnerability("spectre_v2", Vulnerable, "IBRS, STIBP, ARCH_CAPABILITIES"),
+      ]
+    );
+    let amd = SpeculationControl {
+      amd: true,
+      ssb_no: true,
+      ..Default::default()
+    };
+    assert_eq!(
+      amd.vulnerabilities(),
+      vec![
+        vulnerability("mds", NotAffected, ""),
+        vulnerability("spec_store_bypass", NotAffected, ""),
+        vulnerability("spectre_v2", Vulnerable, ""),
+      ]
+    );
+  }
+}
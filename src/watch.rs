@@ -0,0 +1,263 @@
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use napi::{
+  bindgen_prelude::AsyncGenerator,
+  threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode},
+  tokio::sync::{mpsc, Mutex as AsyncMutex},
+  Status,
+};
+use napi_derive::napi;
+
+use crate::{refresh::RefreshKind, LoadAvg};
+
+/// Callback receiving the snapshots of `SysInfo.watch`.
+///
+/// Only one call is queued at a time, samples taken while the event loop is busy are dropped.
+pub type WatchCallback =
+  ThreadsafeFunction<SystemSnapshot, (), SystemSnapshot, Status, false, false, 1>;
+
+#[napi(object)]
+pub struct WatchOptions {
+  /// Sampling interval, defaults to `1000`. It's raised to `MINIMUM_CPU_UPDATE_INTERVAL` if lower.
+  pub interval_ms: Option<u32>,
+  /// What to refresh before every sample, defaults to cpu usage, cpu frequency and memory
+  pub kinds: Option<RefreshKind>,
+  /// How many snapshots the async iterator buffers before dropping new ones, defaults to `1`
+  pub buffer_size: Option<u32>,
+}
+
+#[napi(object, object_from_js = false)]
+pub struct CpuSnapshot {
+  pub name: String,
+  /// Cpu usage in percent
+  pub usage: f64,
+  /// Cpu frequency in `MHz`
+  pub frequency: u32,
+}
+
+#[napi(object, object_from_js = false)]
+/// Values of the system at a point in time, memory is in bytes.
+pub struct SystemSnapshot {
+  /// Milliseconds since UNIX epoch
+  pub timestamp: f64,
+  /// Samples dropped since the previous snapshot because the consumer was too slow
+  pub dropped: u32,
+  pub global_cpu_usage: f64,
+  pub cpus: Vec<CpuSnapshot>,
+  pub total_memory: u64,
+  pub used_memory: u64,
+  pub available_memory: u64,
+  pub total_swap: u64,
+  pub used_swap: u64,
+  pub load_average: LoadAvg,
+}
+
+impl SystemSnapshot {
+  fn new(system: &sysinfo::System, dropped: u32) -> Self {
+    Self {
+      timestamp: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs_f64() * 1000.0)
+        .unwrap_or_default(),
+      dropped,
+      global_cpu_usage: system.global_cpu_usage() as f64,
+      cpus: system
+        .cpus()
+        .iter()
+        .map(|cpu| CpuSnapshot {
+          name: cpu.name().to_string(),
+          usage: cpu.cpu_usage() as f64,
+          frequency: cpu.frequency() as u32,
+        })
+        .collect(),
+      total_memory: system.total_memory(),
+      used_memory: system.used_memory(),
+      available_memory: system.available_memory(),
+      total_swap: system.total_swap(),
+      used_swap: system.used_swap(),
+      load_average: sysinfo::System::load_average().into(),
+    }
+  }
+}
+
+enum Sink {
+  Channel(mpsc::Sender<SystemSnapshot>),
+  Callback(WatchCallback),
+}
+
+enum Delivery {
+  Sent,
+  Dropped,
+  Closed,
+}
+
+impl Sink {
+  fn deliver(&self, snapshot: SystemSnapshot) -> Delivery {
+    match self {
+      Sink::Channel(sender) => match sender.try_send(snapshot) {
+        Ok(()) => Delivery::Sent,
+        Err(mpsc::error::TrySendError::Full(_)) => Delivery::Dropped,
+        Err(mpsc::error::TrySendError::Closed(_)) => Delivery::Closed,
+      },
+      Sink::Callback(callback) => {
+        match callback.call(snapshot, ThreadsafeFunctionCallMode::NonBlocking) {
+          Status::Ok => Delivery::Sent,
+          Status::QueueFull => Delivery::Dropped,
+          _ => Delivery::Closed,
+        }
+      }
+    }
+  }
+}
+
+#[derive(Default)]
+struct StopSignal {
+  stopped: Mutex<bool>,
+  condvar: Condvar,
+}
+
+impl StopSignal {
+  fn stop(&self) {
+    *self.stopped.lock().unwrap_or_else(PoisonError::into_inner) = true;
+    self.condvar.notify_all();
+  }
+
+  /// Sleep until `deadline`, returns `true` if `stop` was called meanwhile
+  fn wait_until(&self, deadline: Instant) -> bool {
+    let mut stopped = self.stopped.lock().unwrap_or_else(PoisonError::into_inner);
+    while !*stopped {
+      let now = Instant::now();
+      if now >= deadline {
+        return false;
+      }
+      stopped = self
+        .condvar
+        .wait_timeout(stopped, deadline - now)
+        .unwrap_or_else(PoisonError::into_inner)
+        .0;
+    }
+    true
+  }
+}
+
+#[napi(async_iterator)]
+/// Handle of a running `SysInfo.watch`, iterate it with `for await` to receive the snapshots.
+///
+/// A callback keeps the process alive and sampling until `stop()` is called, even once the
+/// `Watcher` is garbage collected. The async iterator stops when the `Watcher` is collected.
+pub struct Watcher {
+  stop: Arc<StopSignal>,
+  receiver: Option<Arc<AsyncMutex<mpsc::Receiver<SystemSnapshot>>>>,
+}
+
+impl Watcher {
+  pub(crate) fn spawn(
+    options: Option<WatchOptions>,
+    callback: Option<WatchCallback>,
+  ) -> napi::Result<Self> {
+    let options = options.unwrap_or(WatchOptions {
+      interval_ms: None,
+      kinds: None,
+      buffer_size: None,
+    });
+    let interval = Duration::from_millis(options.interval_ms.unwrap_or(1000) as u64)
+      .max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    let kinds = options.kinds.map(Into::into).unwrap_or_else(|| {
+      sysinfo::RefreshKind::nothing()
+        .with_cpu(sysinfo::CpuRefreshKind::everything())
+        .with_memory(sysinfo::MemoryRefreshKind::everything())
+    });
+    let (sink, receiver) = match callback {
+      Some(callback) => (Sink::Callback(callback), None),
+      None => {
+        let (sender, receiver) = mpsc::channel(options.buffer_size.unwrap_or(1).max(1) as usize);
+        (
+          Sink::Channel(sender),
+          Some(Arc::new(AsyncMutex::new(receiver))),
+        )
+      }
+    };
+    let stop = Arc::new(StopSignal::default());
+    let stop_signal = stop.clone();
+    thread::Builder::new()
+      .name("sysinfo-watch".to_string())
+      .spawn(move || {
+        // A `System` of its own, sampling never waits for nor blocks the `SysInfo` getters
+        let mut system = sysinfo::System::new_with_specifics(kinds);
+        let mut dropped = 0;
+        // Ticks are scheduled from the start time rather than from the end of the previous
+        // sample, so the time spent refreshing doesn't add up as drift.
+        let mut deadline = Instant::now() + interval;
+        while !stop_signal.wait_until(deadline) {
+          system.refresh_specifics(kinds);
+          let snapshot = SystemSnapshot::new(&system, dropped);
+          match sink.deliver(snapshot) {
+            Delivery::Sent => dropped = 0,
+            Delivery::Dropped => dropped += 1,
+            Delivery::Closed => break,
+          }
+          deadline += interval;
+          let now = Instant::now();
+          while deadline <= now {
+            deadline += interval;
+            dropped += 1;
+          }
+        }
+      })
+      .map_err(|err| {
+        napi::Error::new(
+          Status::GenericFailure,
+          format!("Failed to spawn the watch thread: {err}"),
+        )
+      })?;
+    Ok(Self { stop, receiver })
+  }
+}
+
+#[napi]
+impl Watcher {
+  #[napi]
+  /// Stop sampling, the async iterator ends once the buffered snapshots are consumed
+  pub fn stop(&self) {
+    self.stop.stop();
+  }
+}
+
+impl Drop for Watcher {
+  fn drop(&mut self) {
+    // Nothing can read the buffered snapshots anymore, a callback runs until `stop()`
+    if self.receiver.is_some() {
+      self.stop.stop();
+    }
+  }
+}
+
+#[napi]
+impl AsyncGenerator for Watcher {
+  type Yield = SystemSnapshot;
+  type Next = ();
+  type Return = ();
+
+  fn next(
+    &mut self,
+    _value: Option<Self::Next>,
+  ) -> impl std::future::Future<Output = napi::Result<Option<Self::Yield>>> + Send + 'static {
+    let receiver = self.receiver.clone();
+    async move {
+      match receiver {
+        Some(receiver) => Ok(receiver.lock().await.recv().await),
+        None => Ok(None),
+      }
+    }
+  }
+
+  fn complete(
+    &mut self,
+    _value: Option<Self::Return>,
+  ) -> impl std::future::Future<Output = napi::Result<Option<Self::Yield>>> + Send + 'static {
+    self.stop.stop();
+    async move { Ok(None) }
+  }
+}
@@ -0,0 +1,123 @@
+use crate::X86Level;
+
+/// Features required by each x86-64 psABI level, cumulative with the previous ones.
+///
+/// `sce` and `osfxsr` are left out, no 64-bit OS runs without them.
+const LEVELS: [&[&str]; 4] = [
+  &["cmov", "cx8", "fpu", "fxsr", "mmx", "sse", "sse2"],
+  &[
+    "cmpxchg16b",
+    "lahf_lm",
+    "popcnt",
+    "sse3",
+    "sse4_1",
+    "sse4_2",
+    "ssse3",
+  ],
+  &[
+    "avx", "avx2", "bmi1", "bmi2", "f16c", "fma", "lzcnt", "movbe", "osxsave",
+  ],
+  &["avx512f", "avx512bw", "avx512cd", "avx512dq", "avx512vl"],
+];
+
+impl X86Level {
+  fn new(level: u32, missing_for_next: Vec<String>) -> Self {
+    Self {
+      level,
+      name: (level > 0).then(|| format!("x86-64-v{level}")),
+      missing_for_next,
+    }
+  }
+}
+
+/// The highest level whose features are all present, `has` is asked for the names in `LEVELS`
+pub(crate) fn microarch_level(has: impl Fn(&str) -> bool) -> X86Level {
+  let mut level = 0;
+  for features in LEVELS {
+    let missing = features
+      .iter()
+      .filter(|feature| !has(feature))
+      .map(|feature| feature.to_string())
+      .collect::<Vec<_>>();
+    if !missing.is_empty() {
+      return X86Level::new(level, missing);
+    }
+    level += 1;
+  }
+  X86Level::new(level, Vec::new())
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub(crate) fn detect() -> X86Level {
+  use std::arch::is_x86_feature_detected;
+
+  use raw_cpuid::CpuId;
+
+  let cpuid = CpuId::new();
+  let info = cpuid.get_feature_info();
+  let extended = cpuid.get_extended_processor_and_feature_identifiers();
+  microarch_level(|feature| match feature {
+    "cmov" => info.as_ref().is_some_and(|info| info.has_cmov()),
+    "cx8" => info.as_ref().is_some_and(|info| info.has_cmpxchg8b()),
+    "fpu" => info.as_ref().is_some_and(|info| info.has_fpu()),
+    "fxsr" => is_x86_feature_detected!("fxsr"),
+    "mmx" => is_x86_feature_detected!("mmx"),
+    "sse" => is_x86_feature_detected!("sse"),
+    "sse2" => is_x86_feature_detected!("sse2"),
+    "cmpxchg16b" => is_x86_feature_detected!("cmpxchg16b"),
+    "lahf_lm" => extended.as_ref().is_some_and(|info| info.has_lahf_sahf()),
+    "popcnt" => is_x86_feature_detected!("popcnt"),
+    "sse3" => is_x86_feature_detected!("sse3"),
+    "sse4_1" => is_x86_feature_detected!("sse4.1"),
+    "sse4_2" => is_x86_feature_detected!("sse4.2"),
+    "ssse3" => is_x86_feature_detected!("ssse3"),
+    "avx" => is_x86_feature_detected!("avx"),
+    "avx2" => is_x86_feature_detected!("avx2"),
+    "bmi1" => is_x86_feature_detected!("bmi1"),
+    "bmi2" => is_x86_feature_detected!("bmi2"),
+    "f16c" => is_x86_feature_detected!("f16c"),
+    "fma" => is_x86_feature_detected!("fma"),
+    "lzcnt" => is_x86_feature_detected!("lzcnt"),
+    "movbe" => info.as_ref().is_some_and(|info| info.has_movbe()),
+    "osxsave" => info.as_ref().is_some_and(|info| info.has_oxsave()),
+    "avx512f" => is_x86_feature_detected!("avx512f"),
+    "avx512bw" => is_x86_feature_detected!("avx512bw"),
+    "avx512cd" => is_x86_feature_detected!("avx512cd"),
+    "avx512dq" => is_x86_feature_detected!("avx512dq"),
+    "avx512vl" => is_x86_feature_detected!("avx512vl"),
+    _ => false,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn level_without(absent: &[&str]) -> X86Level {
+    microarch_level(|feature| !absent.contains(&feature))
+  }
+
+  #[test]
+  fn every_feature_is_v4() {
+    let level = level_without(&[]);
+    assert_eq!(level.level, 4);
+    assert_eq!(level.name.as_deref(), Some("x86-64-v4"));
+    assert!(level.missing_for_next.is_empty());
+  }
+
+  #[test]
+  fn missing_features_block_the_next_level() {
+    let level = level_without(&["movbe", "avx512f"]);
+    assert_eq!(level.level, 2);
+    assert_eq!(level.name.as_deref(), Some("x86-64-v2"));
+    assert_eq!(level.missing_for_next, ["movbe"]);
+  }
+
+  #[test]
+  fn below_baseline() {
+    let level = level_without(&["cmov", "sse2", "popcnt"]);
+    assert_eq!(level.level, 0);
+    assert_eq!(level.name, None);
+    assert_eq!(level.missing_for_next, ["cmov", "sse2"]);
+  }
+}